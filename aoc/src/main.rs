@@ -0,0 +1,68 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+
+const CARGO_TOML_TEMPLATE: &str = include_str!("template/Cargo.toml.in");
+const MAIN_TEMPLATE: &str = include_str!("template/main.rs.in");
+
+const USAGE: &str = "Usage: aoc new <day>";
+
+/// The workspace root, which is where all of the day crates live
+fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap()
+        .to_path_buf()
+}
+
+/// Creates a new dayNN crate in the workspace. It doesn't need registering
+/// anywhere, since the workspace picks up every `day??/` directory.
+fn new_day(day: u32) -> io::Result<PathBuf> {
+    let name = format!("day{:02}", day);
+    let crate_dir = workspace_root().join(&name);
+
+    if crate_dir.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", crate_dir.display()),
+        ));
+    }
+
+    let src_dir = crate_dir.join("src");
+    fs::create_dir_all(&src_dir)?;
+
+    fs::write(
+        crate_dir.join("Cargo.toml"),
+        CARGO_TOML_TEMPLATE.replace("{{name}}", &name),
+    )?;
+    fs::write(src_dir.join("main.rs"), MAIN_TEMPLATE)?;
+    fs::write(src_dir.join("input.txt"), "")?;
+    fs::write(src_dir.join("example_input.txt"), "")?;
+
+    Ok(crate_dir)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let day = match args.as_slice() {
+        [command, day] if command == "new" => day.parse().ok().filter(|d| (1..=25).contains(d)),
+        _ => None,
+    };
+
+    let day = day.unwrap_or_else(|| {
+        eprintln!("{}", USAGE);
+        eprintln!("<day> must be between 1 and 25");
+        process::exit(2);
+    });
+
+    match new_day(day) {
+        Ok(crate_dir) => println!("Created {}", crate_dir.display()),
+        Err(err) => {
+            eprintln!("Failed to create day {}: {}", day, err);
+            process::exit(1);
+        }
+    }
+}
@@ -1,6 +1,12 @@
-use std::num::ParseIntError;
+use std::collections::VecDeque;
+use std::env;
+use std::error::Error;
+use std::io::{self, BufRead, BufWriter, Write};
 
 use itertools::Itertools;
+use report::Report;
+
+mod report;
 
 fn task1(input: impl Iterator<Item = i32>) -> i32 {
     input
@@ -17,12 +23,129 @@ fn task2(input: impl Iterator<Item = i32>) -> i32 {
         .fold(0, |inc, (a, b)| if b > a { inc + 1 } else { inc })
 }
 
-fn main() -> Result<(), ParseIntError> {
+/// Counts how many sums of `window_size` measurements are larger than the
+/// previous sum. Neighbouring windows share all of their measurements apart
+/// from the first one of the old window and the last one of the new window, so
+/// we only need to compare those two instead of summing up the windows.
+fn depth_increases(input: &[i32], window_size: usize) -> usize {
+    assert!(window_size > 0, "Window size must be at least 1");
+
+    input
+        .iter()
+        .zip(input.iter().skip(window_size))
+        .filter(|(a, b)| b > a)
+        .count()
+}
+
+/// Counts depth increases one measurement at a time, so it only has to
+/// remember the last `window_size` measurements rather than the whole input
+struct SonarSweep {
+    window: VecDeque<i32>,
+    window_size: usize,
+    increases: usize,
+}
+
+impl SonarSweep {
+    fn new(window_size: usize) -> Self {
+        assert!(window_size > 0, "Window size must be at least 1");
+
+        Self {
+            window: VecDeque::with_capacity(window_size + 1),
+            window_size,
+            increases: 0,
+        }
+    }
+
+    /// Adds the next measurement and returns the number of increases so far
+    fn push(&mut self, depth: i32) -> usize {
+        self.window.push_back(depth);
+
+        // Same trick as depth_increases: compare against the measurement that
+        // just left the window
+        if self.window.len() > self.window_size {
+            let oldest = self.window.pop_front().unwrap();
+
+            if depth > oldest {
+                self.increases += 1;
+            }
+        }
+
+        self.increases
+    }
+}
+
+/// Reads measurements line by line, yielding the running number of increases
+/// after each one
+fn running_increases(
+    reader: impl BufRead,
+    window_size: usize,
+) -> impl Iterator<Item = io::Result<usize>> {
+    let mut sweep = SonarSweep::new(window_size);
+
+    reader.lines().map(move |line| {
+        let depth = line?
+            .trim()
+            .parse()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        Ok(sweep.push(depth))
+    })
+}
+
+/// Prints the running number of increases for each measurement in `reader`
+fn stream(reader: impl BufRead, window_size: usize) -> io::Result<()> {
+    let mut out = BufWriter::new(io::stdout().lock());
+
+    for increases in running_increases(reader, window_size) {
+        writeln!(out, "{}", increases?)?;
+    }
+
+    out.flush()
+}
+
+/// Parses a window size from the command line, which has to be at least 1
+fn parse_window_size(input: &str) -> Result<usize, Box<dyn Error>> {
+    match input.parse()? {
+        0 => Err("Window size must be at least 1".into()),
+        window_size => Ok(window_size),
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    // Read a live sonar log from stdin instead of the puzzle input, e.g.
+    // `cargo run -p day01 -- --stream 3 < sonar.log`
+    if let [flag, window_size] = args.as_slice() {
+        if flag == "--stream" {
+            stream(io::stdin().lock(), parse_window_size(window_size)?)?;
+
+            return Ok(());
+        }
+    }
+
     let input: Vec<i32> = include_str!("input.txt")
         .lines()
         .map(str::parse::<i32>)
         .try_collect()?;
 
+    // Characterise the puzzle input instead, e.g.
+    // `cargo run -p day01 -- --report 10 3` or
+    // `cargo run -p day01 -- --report 10 3 --json`
+    if let [flag, window_size, threshold, rest @ ..] = args.as_slice() {
+        if flag == "--report" {
+            let report = Report::new(&input, parse_window_size(window_size)?, threshold.parse()?);
+
+            if rest.iter().any(|a| a == "--json") {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                print!("{}", report);
+            }
+
+            return Ok(());
+        }
+    }
+
     println!(
         "Task 1: {} measurements were larger than the previous measurement",
         task1(input.iter().copied())
@@ -33,5 +156,90 @@ fn main() -> Result<(), ParseIntError> {
         task2(input.iter().copied())
     );
 
+    // Optionally try out a different window size, e.g. `cargo run -p day01 -- 5`
+    if let Some(window_size) = args.first() {
+        let window_size = parse_window_size(window_size)?;
+
+        println!(
+            "Window size {}: {} sums were larger than the previous sum",
+            window_size,
+            depth_increases(&input, window_size)
+        );
+    }
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(input: &str) -> Vec<i32> {
+        input.lines().map(str::parse).map(Result::unwrap).collect()
+    }
+
+    #[test]
+    fn test_example() {
+        let input = parse(include_str!("example_input.txt"));
+
+        assert_eq!(depth_increases(&input, 1), 7);
+        assert_eq!(depth_increases(&input, 3), 5);
+    }
+
+    #[test]
+    fn test_matches_tasks() {
+        for input in [include_str!("example_input.txt"), include_str!("input.txt")] {
+            let input = parse(input);
+
+            assert_eq!(
+                depth_increases(&input, 1),
+                task1(input.iter().copied()) as usize
+            );
+            assert_eq!(
+                depth_increases(&input, 3),
+                task2(input.iter().copied()) as usize
+            );
+        }
+    }
+
+    #[test]
+    fn test_running_increases() {
+        let input = include_str!("example_input.txt").as_bytes();
+        let running: Vec<usize> = running_increases(input, 1).map(Result::unwrap).collect();
+
+        assert_eq!(running, vec![0, 1, 2, 3, 3, 4, 5, 6, 6, 7]);
+    }
+
+    #[test]
+    fn test_stream_matches_depth_increases() {
+        let input = include_str!("input.txt");
+        let parsed = parse(input);
+
+        for window_size in [1, 3, 10] {
+            let last = running_increases(input.as_bytes(), window_size)
+                .map(Result::unwrap)
+                .last()
+                .unwrap();
+
+            assert_eq!(last, depth_increases(&parsed, window_size));
+        }
+    }
+
+    #[test]
+    fn test_stream_invalid_measurement() {
+        let mut running = running_increases("100\nabc\n".as_bytes(), 1);
+
+        assert_eq!(running.next().unwrap().unwrap(), 0);
+        assert_eq!(
+            running.next().unwrap().unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn test_short_input() {
+        assert_eq!(depth_increases(&[], 3), 0);
+        assert_eq!(depth_increases(&[1, 2, 3], 3), 0);
+        assert_eq!(depth_increases(&[1, 2, 3, 4], 3), 1);
+    }
+}
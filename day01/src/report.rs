@@ -0,0 +1,256 @@
+use std::fmt::Display;
+
+use serde::Serialize;
+
+/// A run of strictly increasing measurements
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Run {
+    pub start: usize,
+    pub length: usize,
+}
+
+/// The change in depth between two neighbouring measurements
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Jump {
+    pub index: usize,
+    pub from: i32,
+    pub to: i32,
+}
+
+impl Jump {
+    fn change(&self) -> i32 {
+        self.to - self.from
+    }
+}
+
+/// A measurement that is far from the mean of the measurements before it
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Anomaly {
+    pub index: usize,
+    pub depth: i32,
+    pub rolling_mean: f64,
+    pub std_devs: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub window_size: usize,
+    pub threshold: f64,
+    pub longest_increasing_run: Option<Run>,
+    pub largest_jump: Option<Jump>,
+    pub moving_average: Vec<f64>,
+    pub anomalies: Vec<Anomaly>,
+}
+
+impl Report {
+    /// Analyses the measurements. The moving average and the rolling mean
+    /// both use `window_size` measurements, and a measurement is an anomaly
+    /// if it's more than `threshold` standard deviations away from the rolling
+    /// mean of the measurements before it.
+    pub fn new(input: &[i32], window_size: usize, threshold: f64) -> Self {
+        assert!(window_size > 0, "Window size must be at least 1");
+
+        Self {
+            window_size,
+            threshold,
+            longest_increasing_run: longest_increasing_run(input),
+            largest_jump: largest_jump(input),
+            moving_average: moving_average(input, window_size),
+            anomalies: anomalies(input, window_size, threshold),
+        }
+    }
+}
+
+fn longest_increasing_run(input: &[i32]) -> Option<Run> {
+    if input.is_empty() {
+        return None;
+    }
+
+    let mut longest = Run {
+        start: 0,
+        length: 1,
+    };
+    let mut current = Run {
+        start: 0,
+        length: 1,
+    };
+
+    for (i, pair) in input.windows(2).enumerate() {
+        if pair[1] > pair[0] {
+            current.length += 1;
+        } else {
+            current = Run {
+                start: i + 1,
+                length: 1,
+            };
+        }
+
+        if current.length > longest.length {
+            longest = current;
+        }
+    }
+
+    Some(longest)
+}
+
+/// The biggest change in depth either way between two measurements
+fn largest_jump(input: &[i32]) -> Option<Jump> {
+    input
+        .windows(2)
+        .enumerate()
+        .map(|(i, pair)| Jump {
+            index: i + 1,
+            from: pair[0],
+            to: pair[1],
+        })
+        .max_by_key(|jump| jump.change().abs())
+}
+
+fn moving_average(input: &[i32], window_size: usize) -> Vec<f64> {
+    input
+        .windows(window_size)
+        .map(|window| window.iter().map(|&d| d as f64).sum::<f64>() / window_size as f64)
+        .collect()
+}
+
+fn anomalies(input: &[i32], window_size: usize, threshold: f64) -> Vec<Anomaly> {
+    // Keep running sums of the window so we don't have to add it all up again
+    // for every measurement
+    let mut sum: f64 = input.iter().take(window_size).map(|&d| d as f64).sum();
+    let mut sum_squares: f64 = input
+        .iter()
+        .take(window_size)
+        .map(|&d| (d as f64).powi(2))
+        .sum();
+
+    let mut anomalies = Vec::new();
+
+    for (index, &depth) in input.iter().enumerate().skip(window_size) {
+        let n = window_size as f64;
+        let mean = sum / n;
+        let std_dev = (sum_squares / n - mean.powi(2)).max(0.0).sqrt();
+
+        // If the window is completely flat, any deviation would be infinitely
+        // many standard deviations away, so those are left out
+        if std_dev > 0.0 {
+            let std_devs = (depth as f64 - mean) / std_dev;
+
+            if std_devs.abs() > threshold {
+                anomalies.push(Anomaly {
+                    index,
+                    depth,
+                    rolling_mean: mean,
+                    std_devs,
+                });
+            }
+        }
+
+        let oldest = input[index - window_size] as f64;
+        sum += depth as f64 - oldest;
+        sum_squares += (depth as f64).powi(2) - oldest.powi(2);
+    }
+
+    anomalies
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(run) = &self.longest_increasing_run {
+            writeln!(
+                f,
+                "Longest increasing run: {} measurements starting at #{}",
+                run.length, run.start
+            )?;
+        }
+
+        if let Some(jump) = &self.largest_jump {
+            writeln!(
+                f,
+                "Largest jump: {} -> {} ({:+}) at #{}",
+                jump.from,
+                jump.to,
+                jump.change(),
+                jump.index
+            )?;
+        }
+
+        let min_average = self.moving_average.iter().copied().reduce(f64::min);
+        let max_average = self.moving_average.iter().copied().reduce(f64::max);
+
+        if let Some((min, max)) = min_average.zip(max_average) {
+            writeln!(
+                f,
+                "Moving average over {} measurements: {:.1} to {:.1}",
+                self.window_size, min, max
+            )?;
+        }
+
+        writeln!(
+            f,
+            "{} anomalies more than {} standard deviations from the rolling mean",
+            self.anomalies.len(),
+            self.threshold
+        )?;
+
+        for anomaly in self.anomalies.iter() {
+            writeln!(
+                f,
+                "  #{}: {} (mean {:.1}, {:+.2} standard deviations)",
+                anomaly.index, anomaly.depth, anomaly.rolling_mean, anomaly.std_devs
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_longest_increasing_run() {
+        assert_eq!(longest_increasing_run(&[]), None);
+        assert_eq!(
+            longest_increasing_run(&[5, 1, 2, 3, 3, 4]),
+            Some(Run {
+                start: 1,
+                length: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_largest_jump() {
+        assert_eq!(largest_jump(&[1]), None);
+        assert_eq!(
+            largest_jump(&[10, 12, 2, 5]),
+            Some(Jump {
+                index: 2,
+                from: 12,
+                to: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_moving_average() {
+        assert_eq!(moving_average(&[1, 2, 3, 6], 2), vec![1.5, 2.5, 4.5]);
+        assert!(moving_average(&[1, 2], 3).is_empty());
+    }
+
+    #[test]
+    fn test_anomalies() {
+        let input = [10, 12, 10, 12, 11, 30, 11];
+        let anomalies = anomalies(&input, 4, 3.0);
+
+        assert_eq!(anomalies.len(), 1);
+        assert_eq!(anomalies[0].index, 5);
+        assert_eq!(anomalies[0].depth, 30);
+    }
+
+    #[test]
+    fn test_flat_window_has_no_anomalies() {
+        assert!(anomalies(&[5, 5, 5, 100], 3, 3.0).is_empty());
+    }
+}
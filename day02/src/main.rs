@@ -1,75 +1,229 @@
-#[derive(Debug)]
+use std::env;
+use std::error::Error;
+use std::fmt::{Debug, Display};
+use std::fs;
+
+use parser::Program;
+use trajectory::Trajectory;
+
+mod parser;
+mod planner;
+mod trajectory;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 struct Position {
     horizontal: i32,
     depth: i32,
-    aim: i32,
 }
 
-impl Position {
-    fn new() -> Self {
-        Self {
-            horizontal: 0,
-            depth: 0,
-            aim: 0,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Turn {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Movement {
+    Forward(i32),
+    Back(i32),
+    Up(i32),
+    Down(i32),
+    Turn(Turn),
+}
+
+/// Writes the movement as a command that the parser understands
+impl Display for Movement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Movement::Forward(dist) => write!(f, "forward {}", dist),
+            Movement::Back(dist) => write!(f, "back {}", dist),
+            Movement::Up(dist) => write!(f, "up {}", dist),
+            Movement::Down(dist) => write!(f, "down {}", dist),
+            Movement::Turn(Turn::Left) => write!(f, "turn left"),
+            Movement::Turn(Turn::Right) => write!(f, "turn right"),
         }
     }
+}
 
-    fn apply_movement_task1(&mut self, mov: Movement) {
+/// A way of interpreting the submarine's movements. Each model keeps track of
+/// whatever state it needs on top of the submarine's position, so several
+/// models can be run side by side over the same movements.
+trait MovementModel: Debug {
+    fn apply_movement(&mut self, mov: Movement);
+
+    fn position(&self) -> Position;
+}
+
+/// Task 1: up and down change the depth directly. Like the other models, this
+/// only tracks the submarine's side profile, so turning doesn't do anything.
+#[derive(Debug, Default, Clone)]
+struct Naive {
+    position: Position,
+}
+
+impl MovementModel for Naive {
+    fn apply_movement(&mut self, mov: Movement) {
         match mov {
-            Movement::Forward(dist) => self.horizontal += dist,
-            Movement::Up(dist) => self.depth -= dist,
-            Movement::Down(dist) => self.depth += dist,
+            Movement::Forward(dist) => self.position.horizontal += dist,
+            Movement::Back(dist) => self.position.horizontal -= dist,
+            Movement::Up(dist) => self.position.depth -= dist,
+            Movement::Down(dist) => self.position.depth += dist,
+            Movement::Turn(_) => {}
         }
     }
 
-    fn apply_movement_task2(&mut self, mov: Movement) {
+    fn position(&self) -> Position {
+        self.position
+    }
+}
+
+/// Task 2: up and down change the aim, which changes the depth when moving
+/// forward
+#[derive(Debug, Default, Clone)]
+struct Aimed {
+    position: Position,
+    aim: i32,
+}
+
+impl MovementModel for Aimed {
+    fn apply_movement(&mut self, mov: Movement) {
         match mov {
             Movement::Forward(dist) => {
-                self.horizontal += dist;
-                self.depth += self.aim * dist;
+                self.position.horizontal += dist;
+                self.position.depth += self.aim * dist;
+            }
+            // Reversing keeps the same aim, so it undoes the depth change too
+            Movement::Back(dist) => {
+                self.position.horizontal -= dist;
+                self.position.depth -= self.aim * dist;
             }
             Movement::Up(dist) => self.aim -= dist,
             Movement::Down(dist) => self.aim += dist,
+            Movement::Turn(_) => {}
         }
     }
+
+    fn position(&self) -> Position {
+        self.position
+    }
 }
 
-#[derive(Clone, Copy)]
-enum Movement {
-    Forward(i32),
-    Up(i32),
-    Down(i32),
+/// Prints the shortest commands for each model to reach the target, after
+/// checking that they actually get there
+fn print_plans(target: Position) {
+    let naive_plan = planner::plan_naive(target);
+    let aimed_plan = planner::plan_aimed(target);
+
+    if let Some(plan) = &naive_plan {
+        assert_eq!(planner::replay(Naive::default(), plan), target);
+    }
+
+    if let Some(plan) = &aimed_plan {
+        assert_eq!(planner::replay(Aimed::default(), plan), target);
+    }
+
+    for (name, plan) in [("Task 1", naive_plan), ("Task 2", aimed_plan)] {
+        match plan {
+            Some(plan) => {
+                println!("# {}: {} commands", name, plan.len());
+                plan.iter().for_each(|mov| println!("{}", mov));
+            }
+            None => println!("# {}: can't be reached with i32 commands", name),
+        }
+    }
 }
 
-fn main() {
-    let mut pos1 = Position::new();
-    let mut pos2 = Position::new();
-
-    let movements = include_str!("input.txt").lines().map(|l| {
-        let mut tokens = l.split(' ');
-
-        let dir = tokens.next().expect("No direction found");
-        let dist: i32 = tokens
-            .next()
-            .expect("No distance found")
-            .parse()
-            .expect("Failed to parse distance");
-
-        match dir {
-            "forward" => Movement::Forward(dist),
-            "up" => Movement::Up(dist),
-            "down" => Movement::Down(dist),
-            _ => panic!("Unexpected direction for line {}", l),
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    // Work out which commands reach a position instead, e.g.
+    // `cargo run -p day02 -- --plan 15 60`
+    if let [flag, horizontal, depth] = args.as_slice() {
+        if flag == "--plan" {
+            print_plans(Position {
+                horizontal: horizontal.parse()?,
+                depth: depth.parse()?,
+            });
+
+            return Ok(());
         }
-    });
+    }
 
-    for mov in movements {
-        pos1.apply_movement_task1(mov);
-        pos2.apply_movement_task2(mov);
+    let mut models: Vec<(&str, Box<dyn MovementModel>)> = vec![
+        ("Task 1", Box::new(Naive::default())),
+        ("Task 2", Box::new(Aimed::default())),
+    ];
+
+    let program = Program::parse(include_str!("input.txt"))?;
+
+    let mut trajectories: Vec<Trajectory> = models
+        .iter()
+        .map(|(name, model)| Trajectory::new(name, model.as_ref()))
+        .collect();
+
+    for mov in program.movements() {
+        for ((_, model), trajectory) in models.iter_mut().zip(trajectories.iter_mut()) {
+            trajectory.record(model.as_mut(), mov);
+        }
     }
 
-    println!("Task 1 Final position: {:?}", pos1);
-    println!("Task 1: {}", pos1.horizontal * pos1.depth);
-    println!("Task 2 Final position: {:?}", pos2);
-    println!("Task 2: {}", pos2.horizontal * pos2.depth);
+    for (name, model) in models {
+        let pos = model.position();
+
+        println!("{} Final position: {:?}", name, pos);
+        println!("{}: {}", name, pos.horizontal * pos.depth);
+    }
+
+    // Optionally export the paths the submarines took, e.g.
+    // `cargo run -p day02 -- --csv path.csv --svg path.svg`
+    for arg_pair in args.chunks(2) {
+        match arg_pair {
+            [flag, path] if flag == "--csv" => fs::write(path, trajectory::to_csv(&trajectories))?,
+            [flag, path] if flag == "--svg" => fs::write(path, trajectory::to_svg(&trajectories))?,
+            _ => return Err(format!("Unexpected arguments: {}", arg_pair.join(" ")).into()),
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(mut model: impl MovementModel) -> Position {
+        let program = Program::parse(include_str!("example_input.txt")).unwrap();
+
+        for mov in program.movements() {
+            model.apply_movement(mov);
+        }
+
+        model.position()
+    }
+
+    #[test]
+    fn test_back_undoes_forward() {
+        for mut model in [
+            Box::new(Naive::default()) as Box<dyn MovementModel>,
+            Box::new(Aimed::default()),
+        ] {
+            model.apply_movement(Movement::Down(3));
+            model.apply_movement(Movement::Forward(4));
+            let before = model.position();
+
+            model.apply_movement(Movement::Forward(5));
+            model.apply_movement(Movement::Turn(Turn::Left));
+            model.apply_movement(Movement::Back(5));
+            assert_eq!(model.position(), before);
+        }
+    }
+
+    #[test]
+    fn test_examples() {
+        let naive = run(Naive::default());
+        assert_eq!((naive.horizontal, naive.depth), (15, 10));
+
+        let aimed = run(Aimed::default());
+        assert_eq!((aimed.horizontal, aimed.depth), (15, 60));
+    }
 }
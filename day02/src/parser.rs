@@ -0,0 +1,382 @@
+use std::error::Error;
+use std::fmt::Display;
+use std::iter::{self, Peekable};
+use std::vec::IntoIter;
+
+use crate::{Movement, Turn};
+
+/// A single command in a submarine program
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Move(Movement),
+    Repeat(usize, Vec<Command>),
+}
+
+impl Command {
+    /// The movements this command makes, expanding repeats as they're needed
+    fn movements(&self) -> Box<dyn Iterator<Item = Movement> + '_> {
+        match self {
+            Command::Move(mov) => Box::new(iter::once(*mov)),
+            Command::Repeat(count, body) => {
+                Box::new((0..*count).flat_map(move |_| body.iter().flat_map(Command::movements)))
+            }
+        }
+    }
+}
+
+/// A parsed submarine program. Programs look like this:
+///
+/// ```text
+/// # Comments start with a hash
+/// forward 5
+/// repeat 3 {
+///     down 2
+///     back 1
+/// }
+/// turn left
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Program {
+    pub commands: Vec<Command>,
+}
+
+impl Program {
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser {
+            tokens: tokenize(input).into_iter().peekable(),
+            end: end_position(input),
+        };
+
+        let commands = parser.block(None)?;
+
+        Ok(Self { commands })
+    }
+
+    /// Expands all of the repeat blocks into a flat sequence of movements.
+    /// This is lazy, so big repeat counts don't need lots of memory.
+    pub fn movements(&self) -> impl Iterator<Item = Movement> + '_ {
+        self.commands.iter().flat_map(Command::movements)
+    }
+}
+
+/// An error in a submarine program. Lines and columns start at 1, and columns
+/// count characters rather than bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    fn new(position: (usize, usize), message: String) -> Self {
+        Self {
+            line: position.0,
+            column: position.1,
+            message,
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl Error for ParseError {}
+
+#[derive(Debug, Clone, Copy)]
+struct Token<'a> {
+    text: &'a str,
+    position: (usize, usize),
+}
+
+/// Splits the input into words and braces, skipping comments
+fn tokenize(input: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+
+    for (i, line) in input.lines().enumerate() {
+        let line = line.split('#').next().unwrap();
+        // The byte index and column where the current word starts
+        let mut word_start = None;
+
+        for (column, (j, c)) in line.char_indices().chain([(line.len(), ' ')]).enumerate() {
+            let is_brace = c == '{' || c == '}';
+
+            if c.is_whitespace() || is_brace {
+                if let Some((start, start_column)) = word_start.take() {
+                    tokens.push(Token {
+                        text: &line[start..j],
+                        position: (i + 1, start_column + 1),
+                    });
+                }
+
+                if is_brace {
+                    tokens.push(Token {
+                        text: &line[j..(j + 1)],
+                        position: (i + 1, column + 1),
+                    });
+                }
+            } else if word_start.is_none() {
+                word_start = Some((j, column));
+            }
+        }
+    }
+
+    tokens
+}
+
+/// The position just after the end of the input, for errors about missing tokens
+fn end_position(input: &str) -> (usize, usize) {
+    let lines = input.lines().count().max(1);
+    let last_line_len = input.lines().last().map_or(0, |line| line.chars().count());
+
+    (lines, last_line_len + 1)
+}
+
+struct Parser<'a> {
+    tokens: Peekable<IntoIter<Token<'a>>>,
+    end: (usize, usize),
+}
+
+impl<'a> Parser<'a> {
+    fn next(&mut self, expected: &str) -> Result<Token<'a>, ParseError> {
+        self.tokens.next().ok_or_else(|| {
+            ParseError::new(
+                self.end,
+                format!("expected {}, found end of input", expected),
+            )
+        })
+    }
+
+    fn number<T: std::str::FromStr>(&mut self) -> Result<T, ParseError> {
+        let token = self.next("a number")?;
+
+        token.text.parse().map_err(|_| {
+            ParseError::new(
+                token.position,
+                format!("expected a number, found `{}`", token.text),
+            )
+        })
+    }
+
+    /// Parses commands until the end of the block. `open_brace` is the
+    /// position of the `{` that started this block, or `None` at the top level.
+    fn block(&mut self, open_brace: Option<(usize, usize)>) -> Result<Vec<Command>, ParseError> {
+        let mut commands = Vec::new();
+
+        loop {
+            match (self.tokens.peek(), open_brace) {
+                (None, None) => break,
+                (None, Some(position)) => {
+                    return Err(ParseError::new(
+                        position,
+                        "this `{` is never closed".to_string(),
+                    ))
+                }
+                (Some(token), Some(_)) if token.text == "}" => {
+                    self.tokens.next();
+                    break;
+                }
+                (Some(token), None) if token.text == "}" => {
+                    return Err(ParseError::new(
+                        token.position,
+                        "unexpected `}`".to_string(),
+                    ))
+                }
+                _ => commands.push(self.command()?),
+            }
+        }
+
+        Ok(commands)
+    }
+
+    fn command(&mut self) -> Result<Command, ParseError> {
+        let token = self.next("a command")?;
+
+        let command = match token.text {
+            "forward" => Command::Move(Movement::Forward(self.number()?)),
+            "back" => Command::Move(Movement::Back(self.number()?)),
+            "up" => Command::Move(Movement::Up(self.number()?)),
+            "down" => Command::Move(Movement::Down(self.number()?)),
+            "turn" => {
+                let dir = self.next("`left` or `right`")?;
+
+                let turn = match dir.text {
+                    "left" => Turn::Left,
+                    "right" => Turn::Right,
+                    _ => {
+                        return Err(ParseError::new(
+                            dir.position,
+                            format!("expected `left` or `right`, found `{}`", dir.text),
+                        ))
+                    }
+                };
+
+                Command::Move(Movement::Turn(turn))
+            }
+            "repeat" => {
+                let count = self.number()?;
+                let open_brace = self.next("`{`")?;
+
+                if open_brace.text != "{" {
+                    return Err(ParseError::new(
+                        open_brace.position,
+                        format!("expected `{{`, found `{}`", open_brace.text),
+                    ));
+                }
+
+                Command::Repeat(count, self.block(Some(open_brace.position))?)
+            }
+            _ => {
+                return Err(ParseError::new(
+                    token.position,
+                    format!("unknown command `{}`", token.text),
+                ))
+            }
+        };
+
+        Ok(command)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_puzzle_input_format() {
+        let program = Program::parse(include_str!("example_input.txt")).unwrap();
+
+        assert_eq!(
+            program.movements().collect::<Vec<_>>(),
+            vec![
+                Movement::Forward(5),
+                Movement::Down(5),
+                Movement::Forward(8),
+                Movement::Up(3),
+                Movement::Down(8),
+                Movement::Forward(2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extended_commands() {
+        let input = "\
+# Head out
+forward 5
+
+repeat 2 {
+    down 1 # dive
+    repeat 2 { back 3 }
+}
+turn left
+turn right
+";
+        let program = Program::parse(input).unwrap();
+
+        assert_eq!(
+            program.commands,
+            vec![
+                Command::Move(Movement::Forward(5)),
+                Command::Repeat(
+                    2,
+                    vec![
+                        Command::Move(Movement::Down(1)),
+                        Command::Repeat(2, vec![Command::Move(Movement::Back(3))]),
+                    ]
+                ),
+                Command::Move(Movement::Turn(Turn::Left)),
+                Command::Move(Movement::Turn(Turn::Right)),
+            ]
+        );
+
+        assert_eq!(
+            program.movements().collect::<Vec<_>>(),
+            vec![
+                Movement::Forward(5),
+                Movement::Down(1),
+                Movement::Back(3),
+                Movement::Back(3),
+                Movement::Down(1),
+                Movement::Back(3),
+                Movement::Back(3),
+                Movement::Turn(Turn::Left),
+                Movement::Turn(Turn::Right),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_huge_repeats() {
+        let program = Program::parse("repeat 4000000000 { repeat 4000000000 { up 1 } }").unwrap();
+
+        assert_eq!(
+            program.movements().take(3).collect::<Vec<_>>(),
+            vec![Movement::Up(1); 3]
+        );
+    }
+
+    #[test]
+    fn test_unknown_command() {
+        let error = Program::parse("forward 1\n  sideways 3\n").unwrap_err();
+
+        assert_eq!(
+            error,
+            ParseError::new((2, 3), "unknown command `sideways`".to_string())
+        );
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 3: unknown command `sideways`"
+        );
+    }
+
+    #[test]
+    fn test_bad_arguments() {
+        let error = Program::parse("up ten").unwrap_err();
+        assert_eq!(error.message, "expected a number, found `ten`");
+
+        let error = Program::parse("turn around").unwrap_err();
+        assert_eq!(error.message, "expected `left` or `right`, found `around`");
+
+        let error = Program::parse("forward").unwrap_err();
+        assert_eq!(
+            (error.line, error.column, error.message.as_str()),
+            (1, 8, "expected a number, found end of input")
+        );
+    }
+
+    #[test]
+    fn test_unbalanced_braces() {
+        let error = Program::parse("repeat 2 {\n  forward 1\n").unwrap_err();
+        assert_eq!(
+            error,
+            ParseError::new((1, 10), "this `{` is never closed".to_string())
+        );
+
+        let error = Program::parse("forward 1 }").unwrap_err();
+        assert_eq!(
+            error,
+            ParseError::new((1, 11), "unexpected `}`".to_string())
+        );
+
+        let error = Program::parse("repeat 2 forward 1").unwrap_err();
+        assert_eq!(error.message, "expected `{`, found `forward`");
+    }
+
+    #[test]
+    fn test_columns_count_characters() {
+        // An ideographic space is one character but three bytes
+        let error = Program::parse("forward\u{3000}1 }").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 11));
+
+        let error = Program::parse("forward\u{3000}").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 9));
+    }
+}
@@ -0,0 +1,254 @@
+use crate::{Movement, MovementModel, Position};
+
+/// Moves the submarine `dist` along, using forward or back depending on the
+/// sign. There's no way to go back by `i32::MIN`, so that gives None.
+fn horizontal_move(dist: i32) -> Option<Movement> {
+    if dist > 0 {
+        Some(Movement::Forward(dist))
+    } else {
+        dist.checked_neg().map(Movement::Back)
+    }
+}
+
+/// Changes the depth or aim by `dist`, using down or up depending on the sign.
+/// Like `horizontal_move`, this gives None for `i32::MIN`.
+fn vertical_move(dist: i32) -> Option<Movement> {
+    if dist > 0 {
+        Some(Movement::Down(dist))
+    } else {
+        dist.checked_neg().map(Movement::Up)
+    }
+}
+
+/// The shortest list of movements that gets the Task 1 submarine to `target`.
+/// Up and down change the depth directly, so this is at most two movements.
+/// Returns None if the movements don't fit in an i32.
+pub fn plan_naive(target: Position) -> Option<Vec<Movement>> {
+    let mut plan = Vec::new();
+
+    if target.horizontal != 0 {
+        plan.push(horizontal_move(target.horizontal)?);
+    }
+
+    if target.depth != 0 {
+        plan.push(vertical_move(target.depth)?);
+    }
+
+    Some(plan)
+}
+
+/// The shortest list of movements that gets the Task 2 submarine to `target`.
+///
+/// The depth only changes when moving horizontally with some aim, so:
+/// - If the depth is 0, we can just move horizontally
+/// - If the horizontal distance divides the depth, we can aim and then move
+///   the whole way in one go
+/// - Otherwise, no two movements will do. Instead we move most of the way
+///   without any aim, then aim steeply enough to cover the whole depth in the
+///   last step.
+///
+/// Returns None if the movements, or the depth changes while reversing, don't
+/// fit in an i32.
+pub fn plan_aimed(target: Position) -> Option<Vec<Movement>> {
+    let Position { horizontal, depth } = target;
+
+    if depth == 0 {
+        return plan_naive(target);
+    }
+
+    if horizontal != 0 && depth.checked_rem(horizontal)? == 0 {
+        // Reversing subtracts `aim * dist`, which is `-depth`
+        if horizontal < 0 {
+            depth.checked_neg()?;
+        }
+
+        return Some(vec![
+            vertical_move(depth.checked_div(horizontal)?)?,
+            horizontal_move(horizontal)?,
+        ]);
+    }
+
+    // Since horizontal doesn't divide depth here, it can't be 1 or -1, so the
+    // first move is never zero. The last step goes towards the target so that
+    // the first move can't overflow, and when reversing the aim is flipped.
+    let (first, aim, last) = if horizontal > 0 {
+        (horizontal - 1, depth, Movement::Forward(1))
+    } else {
+        (horizontal + 1, depth.checked_neg()?, Movement::Back(1))
+    };
+
+    Some(vec![horizontal_move(first)?, vertical_move(aim)?, last])
+}
+
+/// Runs the plan through a fresh model to check where it ends up
+pub fn replay(mut model: impl MovementModel, plan: &[Movement]) -> Position {
+    for &mov in plan {
+        model.apply_movement(mov);
+    }
+
+    model.position()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Program;
+    use crate::{Aimed, Naive};
+
+    fn targets() -> impl Iterator<Item = Position> {
+        (-12..=12)
+            .flat_map(|horizontal| (-30..=30).map(move |depth| Position { horizontal, depth }))
+    }
+
+    /// Tries every possible plan of up to `len` movements, each moving up to
+    /// `max_dist`, to find the shortest one that reaches the target
+    fn brute_force_len(target: Position, len: usize, max_dist: i32) -> Option<usize> {
+        let moves: Vec<Movement> = (1..=max_dist)
+            .flat_map(|d| {
+                [
+                    Movement::Forward(d),
+                    Movement::Back(d),
+                    Movement::Up(d),
+                    Movement::Down(d),
+                ]
+            })
+            .collect();
+
+        let mut frontier = vec![Aimed::default()];
+
+        for steps in 0..=len {
+            if frontier.iter().any(|m| m.position() == target) {
+                return Some(steps);
+            }
+
+            if steps == len {
+                break;
+            }
+
+            frontier = frontier
+                .iter()
+                .flat_map(|model| {
+                    moves.iter().map(move |&mov| {
+                        let mut next = model.clone();
+                        next.apply_movement(mov);
+                        next
+                    })
+                })
+                .collect();
+        }
+
+        None
+    }
+
+    #[test]
+    fn test_plans_reach_target() {
+        for target in targets() {
+            assert_eq!(
+                replay(Naive::default(), &plan_naive(target).unwrap()),
+                target
+            );
+            assert_eq!(
+                replay(Aimed::default(), &plan_aimed(target).unwrap()),
+                target
+            );
+        }
+    }
+
+    #[test]
+    fn test_extreme_targets() {
+        let extremes = [i32::MIN, i32::MIN + 1, -2, -1, 0, 1, 2, i32::MAX];
+
+        for horizontal in extremes {
+            for depth in extremes {
+                let target = Position { horizontal, depth };
+                let has_min = horizontal == i32::MIN || depth == i32::MIN;
+
+                match plan_naive(target) {
+                    Some(plan) => assert_eq!(replay(Naive::default(), &plan), target),
+                    None => assert!(has_min, "No naive plan for {:?}", target),
+                }
+
+                match plan_aimed(target) {
+                    Some(plan) => assert_eq!(replay(Aimed::default(), &plan), target),
+                    None => assert!(has_min, "No aimed plan for {:?}", target),
+                }
+            }
+        }
+
+        assert_eq!(
+            plan_aimed(Position {
+                horizontal: 2,
+                depth: i32::MIN
+            }),
+            Some(vec![Movement::Up(1 << 30), Movement::Forward(2)])
+        );
+        assert_eq!(
+            plan_naive(Position {
+                horizontal: i32::MIN,
+                depth: 0
+            }),
+            None
+        );
+    }
+
+    #[test]
+    fn test_naive_plan_length() {
+        assert_eq!(plan_naive(Position::default()), Some(vec![]));
+        assert_eq!(
+            plan_naive(Position {
+                horizontal: 15,
+                depth: 10
+            }),
+            Some(vec![Movement::Forward(15), Movement::Down(10)])
+        );
+    }
+
+    #[test]
+    fn test_aimed_plans_are_minimal() {
+        for horizontal in -3..=3 {
+            for depth in -6..=6 {
+                let target = Position { horizontal, depth };
+                let plan = plan_aimed(target).unwrap();
+
+                assert_eq!(
+                    brute_force_len(target, plan.len(), 6),
+                    Some(plan.len()),
+                    "Plan {:?} for {:?} isn't minimal",
+                    plan,
+                    target
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_plan_parses_back() {
+        let plan = plan_aimed(Position {
+            horizontal: -7,
+            depth: 30,
+        })
+        .unwrap();
+        let text: String = plan.iter().map(|mov| format!("{}\n", mov)).collect();
+
+        assert_eq!(
+            Program::parse(&text)
+                .unwrap()
+                .movements()
+                .collect::<Vec<_>>(),
+            plan
+        );
+    }
+
+    #[test]
+    fn test_aimed_example() {
+        let target = Position {
+            horizontal: 15,
+            depth: 60,
+        };
+
+        assert_eq!(
+            plan_aimed(target),
+            Some(vec![Movement::Down(4), Movement::Forward(15)])
+        );
+    }
+}
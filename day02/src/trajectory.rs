@@ -0,0 +1,204 @@
+use std::fmt::Write;
+
+use crate::{Movement, MovementModel, Position};
+
+const SVG_WIDTH: f64 = 800.0;
+const SVG_HEIGHT: f64 = 400.0;
+const SVG_MARGIN: f64 = 40.0;
+const COLOURS: [&str; 4] = ["#1f77b4", "#d62728", "#2ca02c", "#9467bd"];
+
+/// Every position a model's submarine went through, starting from where it was
+/// before the first movement
+#[derive(Debug)]
+pub struct Trajectory<'a> {
+    pub name: &'a str,
+    pub positions: Vec<Position>,
+}
+
+impl<'a> Trajectory<'a> {
+    pub fn new(name: &'a str, model: &dyn MovementModel) -> Self {
+        Self {
+            name,
+            positions: vec![model.position()],
+        }
+    }
+
+    /// Applies the movement to the model and records where it ends up
+    pub fn record(&mut self, model: &mut dyn MovementModel, mov: Movement) {
+        model.apply_movement(mov);
+        self.positions.push(model.position());
+    }
+}
+
+/// One row per position per model
+pub fn to_csv(trajectories: &[Trajectory]) -> String {
+    let mut csv = String::from("model,step,horizontal,depth\n");
+
+    for trajectory in trajectories {
+        for (step, pos) in trajectory.positions.iter().enumerate() {
+            writeln!(
+                csv,
+                "{},{},{},{}",
+                trajectory.name, step, pos.horizontal, pos.depth
+            )
+            .unwrap();
+        }
+    }
+
+    csv
+}
+
+/// Plots depth against horizontal position, with depth increasing downwards.
+/// All of the trajectories share the same axes so they can be compared.
+pub fn to_svg(trajectories: &[Trajectory]) -> String {
+    let all_positions = || trajectories.iter().flat_map(|t| t.positions.iter());
+
+    // Always include the starting point so the surface is on the plot
+    let (min_x, max_x) = all_positions()
+        .map(|p| p.horizontal)
+        .fold((0, 0), |(min, max), x| (min.min(x), max.max(x)));
+    let (min_y, max_y) = all_positions()
+        .map(|p| p.depth)
+        .fold((0, 0), |(min, max), y| (min.min(y), max.max(y)));
+
+    // Avoid dividing by zero if nothing moved
+    let scale_x = (SVG_WIDTH - 2.0 * SVG_MARGIN) / (max_x - min_x).max(1) as f64;
+    let scale_y = (SVG_HEIGHT - 2.0 * SVG_MARGIN) / (max_y - min_y).max(1) as f64;
+    let to_svg_point = |p: &Position| {
+        (
+            SVG_MARGIN + (p.horizontal - min_x) as f64 * scale_x,
+            SVG_MARGIN + (p.depth - min_y) as f64 * scale_y,
+        )
+    };
+
+    let mut svg = String::new();
+
+    writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+        w = SVG_WIDTH,
+        h = SVG_HEIGHT
+    )
+    .unwrap();
+    writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#).unwrap();
+
+    // Axes, labelled with the range of each one
+    writeln!(
+        svg,
+        r#"<g stroke="black" fill="none"><rect x="{m}" y="{m}" width="{w}" height="{h}"/></g>"#,
+        m = SVG_MARGIN,
+        w = SVG_WIDTH - 2.0 * SVG_MARGIN,
+        h = SVG_HEIGHT - 2.0 * SVG_MARGIN
+    )
+    .unwrap();
+    writeln!(
+        svg,
+        r#"<g font-family="sans-serif" font-size="12"><text x="{m}" y="{top}">depth {min_y}</text><text x="{m}" y="{bottom}">depth {max_y}</text><text x="{right}" y="{bottom}" text-anchor="end">horizontal {min_x} to {max_x}</text></g>"#,
+        m = SVG_MARGIN,
+        top = SVG_MARGIN - 6.0,
+        bottom = SVG_HEIGHT - SVG_MARGIN + 16.0,
+        right = SVG_WIDTH - SVG_MARGIN,
+    )
+    .unwrap();
+
+    for (i, trajectory) in trajectories.iter().enumerate() {
+        let colour = COLOURS[i % COLOURS.len()];
+        let points: Vec<String> = trajectory
+            .positions
+            .iter()
+            .map(to_svg_point)
+            .map(|(x, y)| format!("{:.2},{:.2}", x, y))
+            .collect();
+
+        writeln!(
+            svg,
+            r#"<polyline fill="none" stroke="{}" stroke-width="1.5" points="{}"/>"#,
+            colour,
+            points.join(" ")
+        )
+        .unwrap();
+
+        // Legend in the top right corner
+        writeln!(
+            svg,
+            r#"<text x="{}" y="{}" font-family="sans-serif" font-size="12" fill="{}" text-anchor="end">{}</text>"#,
+            SVG_WIDTH - SVG_MARGIN - 6.0,
+            SVG_MARGIN + 16.0 * (i + 1) as f64,
+            colour,
+            trajectory.name
+        )
+        .unwrap();
+    }
+
+    writeln!(svg, "</svg>").unwrap();
+
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Aimed, Naive};
+
+    fn example_trajectories() -> (Naive, Aimed, Vec<Trajectory<'static>>) {
+        let mut naive = Naive::default();
+        let mut aimed = Aimed::default();
+        let mut trajectories = vec![
+            Trajectory::new("naive", &naive),
+            Trajectory::new("aimed", &aimed),
+        ];
+
+        for mov in [
+            Movement::Forward(5),
+            Movement::Down(5),
+            Movement::Forward(8),
+        ] {
+            trajectories[0].record(&mut naive, mov);
+            trajectories[1].record(&mut aimed, mov);
+        }
+
+        (naive, aimed, trajectories)
+    }
+
+    #[test]
+    fn test_record() {
+        let (naive, aimed, trajectories) = example_trajectories();
+
+        assert_eq!(trajectories[0].positions.len(), 4);
+        assert_eq!(trajectories[0].positions.last(), Some(&naive.position()));
+        assert_eq!(trajectories[1].positions.last(), Some(&aimed.position()));
+    }
+
+    #[test]
+    fn test_csv() {
+        let (_, _, trajectories) = example_trajectories();
+
+        assert_eq!(
+            to_csv(&trajectories),
+            "\
+model,step,horizontal,depth
+naive,0,0,0
+naive,1,5,0
+naive,2,5,5
+naive,3,13,5
+aimed,0,0,0
+aimed,1,5,0
+aimed,2,5,0
+aimed,3,13,40
+"
+        );
+    }
+
+    #[test]
+    fn test_svg() {
+        let (_, _, trajectories) = example_trajectories();
+        let svg = to_svg(&trajectories);
+
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches("<polyline").count(), 2);
+
+        // The aimed submarine ends up in the bottom right corner
+        assert!(svg.contains("760.00,360.00\"/>"));
+    }
+}
@@ -0,0 +1,78 @@
+use std::fmt::Display;
+
+/// How many 0s and 1s there are in a column
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BitCounts {
+    pub zeros: usize,
+    pub ones: usize,
+}
+
+impl BitCounts {
+    pub fn is_tie(&self) -> bool {
+        self.zeros == self.ones
+    }
+}
+
+/// Whether to keep the bit that most numbers have, or the one fewest have
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Keep {
+    Majority,
+    Minority,
+}
+
+/// How to pick a bit in each column, including what to do when there are as
+/// many 0s as 1s
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Criteria {
+    pub keep: Keep,
+    pub on_tie: bool,
+}
+
+impl Criteria {
+    /// The puzzle's rules for each rating
+    pub const GAMMA: Self = Self::new(Keep::Majority, false);
+    pub const EPSILON: Self = Self::new(Keep::Minority, true);
+    pub const OXYGEN: Self = Self::new(Keep::Majority, true);
+    pub const CO2: Self = Self::new(Keep::Minority, false);
+
+    pub const fn new(keep: Keep, on_tie: bool) -> Self {
+        Self { keep, on_tie }
+    }
+
+    pub fn pick(&self, counts: BitCounts) -> bool {
+        if counts.is_tie() {
+            self.on_tie
+        } else {
+            (counts.ones > counts.zeros) == (self.keep == Keep::Majority)
+        }
+    }
+}
+
+impl Display for Criteria {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let keep = match self.keep {
+            Keep::Majority => "most",
+            Keep::Minority => "least",
+        };
+
+        write!(f, "{} common, ties keep {}", keep, self.on_tie as u8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pick() {
+        let more_ones = BitCounts { zeros: 2, ones: 3 };
+        let tie = BitCounts { zeros: 2, ones: 2 };
+
+        assert!(Criteria::OXYGEN.pick(more_ones));
+        assert!(Criteria::OXYGEN.pick(tie));
+        assert!(!Criteria::CO2.pick(more_ones));
+        assert!(!Criteria::CO2.pick(tie));
+        assert!(!Criteria::GAMMA.pick(tie));
+        assert!(Criteria::EPSILON.pick(tie));
+    }
+}
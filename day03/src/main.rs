@@ -1,36 +1,146 @@
-use std::cmp::Ordering;
-
-/// Converts an iterator of bools representing the bits of a number into that number
-fn bool_iter_to_num(it: impl DoubleEndedIterator<Item = bool>) -> usize {
-    it.map(|b| b as usize)
-        .rev()
-        .enumerate()
-        .fold(0, |acc, (i, b)| acc + (b << i))
+use std::env;
+use std::error::Error;
+use std::fmt::Display;
+use std::fs;
+
+use criteria::{BitCounts, Criteria};
+use trie::Trie;
+
+mod criteria;
+mod trie;
+
+/// The widest numbers that fit in the report
+const MAX_WIDTH: usize = u128::BITS as usize;
+
+#[derive(Debug, PartialEq, Eq)]
+enum ReportError {
+    Empty,
+    InvalidDigit {
+        line: usize,
+        digit: char,
+    },
+    TooWide {
+        line: usize,
+        width: usize,
+    },
+    MismatchedWidth {
+        line: usize,
+        width: usize,
+        expected: usize,
+    },
+}
+
+impl Display for ReportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReportError::Empty => write!(f, "the report is empty"),
+            ReportError::InvalidDigit { line, digit } => {
+                write!(f, "line {}: {:?} is not a binary digit", line, digit)
+            }
+            ReportError::TooWide { line, width } => write!(
+                f,
+                "line {}: {} bits is wider than the maximum of {}",
+                line, width, MAX_WIDTH
+            ),
+            ReportError::MismatchedWidth {
+                line,
+                width,
+                expected,
+            } => write!(
+                f,
+                "line {}: {} bits wide, but the first line is {} bits wide",
+                line, width, expected
+            ),
+        }
+    }
+}
+
+impl Error for ReportError {}
+
+/// The diagnostic report, with each line packed into an integer. Columns are
+/// numbered from the left, so column 0 is the most significant bit.
+#[derive(Debug)]
+struct Report {
+    numbers: Vec<u128>,
+    width: usize,
 }
 
-fn task1(lines: &[&str]) {
-    let line_length = lines[0].len();
-
-    // Convert each line into an iterator of bools, then flatten it so we get
-    // one stream of bools
-    let bits = lines.iter().flat_map(|&l| l.chars()).map(|c| c == '1');
-
-    // This counts the number of 1s in each column and returns true if they are
-    // more common than 0. This results in an iterator of bools that corresponds
-    // to the bits in the gamma rate
-    let most_common_bits = (0..line_length)
-        .map(|i| {
-            bits.clone()
-                .skip(i)
-                .step_by(line_length)
-                .filter(|&b| b)
-                .count()
-        })
-        .map(|num_ones| num_ones > (lines.len() - num_ones));
-
-    let gamma_rate = bool_iter_to_num(most_common_bits.clone());
-    // If we flip the bits of gamma_rate, we get epsilon rate
-    let epsilon_rate = bool_iter_to_num(most_common_bits.map(|b| !b));
+impl Report {
+    fn parse<'a>(lines: impl Iterator<Item = &'a str>) -> Result<Self, ReportError> {
+        let mut numbers = Vec::new();
+        let mut width = None;
+
+        for (i, line) in lines.enumerate() {
+            let line_num = i + 1;
+
+            if line.len() > MAX_WIDTH {
+                return Err(ReportError::TooWide {
+                    line: line_num,
+                    width: line.len(),
+                });
+            }
+
+            let expected = *width.get_or_insert(line.len());
+
+            if line.len() != expected {
+                return Err(ReportError::MismatchedWidth {
+                    line: line_num,
+                    width: line.len(),
+                    expected,
+                });
+            }
+
+            let num = line.chars().try_fold(0, |acc, c| match c {
+                '0' => Ok(acc << 1),
+                '1' => Ok((acc << 1) | 1),
+                _ => Err(ReportError::InvalidDigit {
+                    line: line_num,
+                    digit: c,
+                }),
+            })?;
+
+            numbers.push(num);
+        }
+
+        match width {
+            Some(width) if width > 0 => Ok(Self { numbers, width }),
+            _ => Err(ReportError::Empty),
+        }
+    }
+
+    /// A number with a 1 in just this column
+    fn column_mask(&self, column: usize) -> u128 {
+        1 << (self.width - 1 - column)
+    }
+
+    /// How many 0s and 1s there are in each column
+    fn column_counts(&self) -> Vec<BitCounts> {
+        (0..self.width)
+            .map(|column| {
+                let mask = self.column_mask(column);
+                let ones = self.numbers.iter().filter(|&&num| num & mask != 0).count();
+
+                BitCounts {
+                    zeros: self.numbers.len() - ones,
+                    ones,
+                }
+            })
+            .collect()
+    }
+
+    /// Builds a number by picking a bit for each column of the whole report
+    fn rate_columns(&self, criteria: Criteria) -> u128 {
+        self.column_counts()
+            .into_iter()
+            .enumerate()
+            .filter(|&(_, counts)| criteria.pick(counts))
+            .fold(0, |acc, (column, _)| acc | self.column_mask(column))
+    }
+}
+
+fn task1(report: &Report) {
+    let gamma_rate = report.rate_columns(Criteria::GAMMA);
+    let epsilon_rate = report.rate_columns(Criteria::EPSILON);
 
     println!(
         "Task 1: Gamma rate = {}, Epsilon rate = {}",
@@ -39,53 +149,71 @@ fn task1(lines: &[&str]) {
     println!("Task 1 Answer: {}", gamma_rate * epsilon_rate);
 }
 
-// If most_common is true, look for the most common value, otherwise look for
-// least common value
-fn search_for_rating<'a>(search_space: &[&'a str], bit_idx: usize, most_common: bool) -> &'a str {
+/// Searches a sorted search space for the number that matches the criteria.
+/// If we sort the numbers, the ones that share a prefix end up next to each
+/// other, so this always agrees with the trie. It's only used to check it.
+#[cfg(test)]
+fn search_for_rating(
+    report: &Report,
+    search_space: &[u128],
+    column: usize,
+    criteria: Criteria,
+) -> u128 {
+    use std::cmp::Ordering;
+
+    // If there are duplicates, they're all the same once every column has
+    // been looked at
+    if column == report.width {
+        return search_space[0];
+    }
+
     match search_space.len().cmp(&1) {
-        // Base case: we've found the line we want
+        // Base case: we've found the number we want
         Ordering::Equal => search_space[0],
         // Alternative case: we need to reduce the search space
         Ordering::Greater => {
-            // Find the index of the first 1 in the bit_idx column
+            let mask = report.column_mask(column);
+
+            // Since the search space is sorted, all of the numbers with a 0 in
+            // this column come before the ones with a 1, so the index of the
+            // first 1 is the number of 0s
             let one_idx = search_space
                 .iter()
-                .map(|l| l.chars().nth(bit_idx).unwrap())
-                .position(|b| b == '1')
-                // If there aren't any 1s, return the last index
-                .unwrap_or(search_space.len() - 1);
-
-            // Use the index of the first 1 in relation to the halfway point of
-            // the search space to work out which number is most common. We then
-            // reduce the search space to only the lines which have that number
-            // in the bit_idx column.
-            // The xor shenangians will flip the result of the condition when
-            // most_common is false so we find the least common value instead
-            let new_search_space = if (one_idx <= search_space.len() / 2) ^ !most_common {
-                // 1s are the most/least common
+                .position(|&num| num & mask != 0)
+                .unwrap_or(search_space.len());
+
+            let counts = BitCounts {
+                zeros: one_idx,
+                ones: search_space.len() - one_idx,
+            };
+
+            // If all of the numbers have the same bit here, keep all of them
+            let keep_ones = match counts {
+                BitCounts { ones: 0, .. } => false,
+                BitCounts { zeros: 0, .. } => true,
+                _ => criteria.pick(counts),
+            };
+
+            // Reduce the search space to only the numbers which have that bit
+            // in the column
+            let new_search_space = if keep_ones {
                 &search_space[one_idx..search_space.len()]
             } else {
-                // 0s are the most/least common
                 &search_space[0..one_idx]
             };
 
-            // Search the next column using the remaining lines
-            search_for_rating(new_search_space, bit_idx + 1, most_common)
+            // Search the next column using the remaining numbers
+            search_for_rating(report, new_search_space, column + 1, criteria)
         }
         Ordering::Less => unreachable!(),
     }
 }
 
-fn task2(lines: &mut [&str]) {
-    // If we sort the lines lexicographically, we can recursively search for the
-    // line we want
-    lines.sort_unstable();
-
-    let o2_line = search_for_rating(lines, 0, true);
-    let co2_line = search_for_rating(lines, 0, false);
+fn task2(report: &Report) {
+    let trie = Trie::new(&report.numbers, report.width);
 
-    let o2_rating = bool_iter_to_num(o2_line.chars().map(|c| c == '1'));
-    let co2_rating = bool_iter_to_num(co2_line.chars().map(|c| c == '1'));
+    let (o2_rating, _) = trie.rating(Criteria::OXYGEN);
+    let (co2_rating, _) = trie.rating(Criteria::CO2);
 
     println!(
         "Task 2: O2 generator rating = {}, CO2 scrubber rating = {}",
@@ -94,9 +222,195 @@ fn task2(lines: &mut [&str]) {
     println!("Task 2 Answer: {}", o2_rating * co2_rating);
 }
 
-fn main() {
-    let mut lines: Vec<&str> = include_str!("input.txt").lines().collect();
+/// Prints how many 0s and 1s there are in each column, marking the ties
+fn print_column_counts(column_counts: &[BitCounts]) {
+    println!("  Column    0s    1s");
+
+    for (column, counts) in column_counts.iter().enumerate() {
+        println!(
+            "  {:>6}{:>6}{:>6}{}",
+            column,
+            counts.zeros,
+            counts.ones,
+            if counts.is_tie() { "  tie" } else { "" }
+        );
+    }
+}
 
-    task1(&lines);
-    task2(&mut lines);
+/// Prints the counts for the whole report, then the counts of the numbers that
+/// were left in each column while searching for each rating
+fn analyse(report: &Report) {
+    println!("Whole report ({} numbers):", report.numbers.len());
+    print_column_counts(&report.column_counts());
+
+    let trie = Trie::new(&report.numbers, report.width);
+
+    for (name, criteria) in [
+        ("O2 generator", Criteria::OXYGEN),
+        ("CO2 scrubber", Criteria::CO2),
+    ] {
+        let (rating, counts) = trie.rating(criteria);
+
+        println!(
+            "{} rating ({}): {:0width$b} = {}",
+            name,
+            criteria,
+            rating,
+            rating,
+            width = report.width
+        );
+        print_column_counts(&counts);
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    // Look at the counts in any report instead, e.g.
+    // `cargo run -p day03 -- --analyse report.txt`
+    if let [flag, path] = args.as_slice() {
+        if flag == "--analyse" {
+            let input = fs::read_to_string(path)?;
+            analyse(&Report::parse(input.lines())?);
+
+            return Ok(());
+        }
+    }
+
+    let report = Report::parse(include_str!("input.txt").lines())?;
+
+    task1(&report);
+    task2(&report);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let report = Report::parse(include_str!("example_input.txt").lines()).unwrap();
+
+        assert_eq!(report.width, 5);
+        assert_eq!(report.numbers[0], 0b00100);
+        assert_eq!(
+            report
+                .column_counts()
+                .iter()
+                .map(|c| c.ones)
+                .collect::<Vec<_>>(),
+            vec![7, 5, 8, 7, 5]
+        );
+    }
+
+    #[test]
+    fn test_wide_numbers() {
+        let wide = "1".repeat(128);
+        let report = Report::parse([wide.as_str()].into_iter()).unwrap();
+
+        assert_eq!(report.numbers, vec![u128::MAX]);
+        assert_eq!(report.rate_columns(Criteria::GAMMA), u128::MAX);
+        assert_eq!(report.column_mask(0), 1 << 127);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Report::parse(std::iter::empty()).unwrap_err(),
+            ReportError::Empty
+        );
+        assert_eq!(
+            Report::parse(["0101", "011"].into_iter()).unwrap_err(),
+            ReportError::MismatchedWidth {
+                line: 2,
+                width: 3,
+                expected: 4
+            }
+        );
+        assert_eq!(
+            Report::parse(["0121"].into_iter()).unwrap_err(),
+            ReportError::InvalidDigit {
+                line: 1,
+                digit: '2'
+            }
+        );
+
+        let too_wide = "0".repeat(129);
+        assert_eq!(
+            Report::parse([too_wide.as_str()].into_iter()).unwrap_err(),
+            ReportError::TooWide {
+                line: 1,
+                width: 129
+            }
+        );
+    }
+
+    #[test]
+    fn test_example_ratings() {
+        let report = Report::parse(include_str!("example_input.txt").lines()).unwrap();
+        let mut sorted = report.numbers.clone();
+        sorted.sort_unstable();
+
+        assert_eq!(search_for_rating(&report, &sorted, 0, Criteria::OXYGEN), 23);
+        assert_eq!(search_for_rating(&report, &sorted, 0, Criteria::CO2), 10);
+
+        let trie = Trie::new(&report.numbers, report.width);
+        assert_eq!(trie.rating(Criteria::OXYGEN).0, 23);
+        assert_eq!(trie.rating(Criteria::CO2).0, 10);
+    }
+
+    #[test]
+    fn test_trie_matches_search() {
+        let report = Report::parse(include_str!("input.txt").lines()).unwrap();
+        let mut sorted = report.numbers.clone();
+        sorted.sort_unstable();
+
+        let trie = Trie::new(&report.numbers, report.width);
+        assert_eq!(
+            trie.rating(Criteria::OXYGEN).0,
+            search_for_rating(&report, &sorted, 0, Criteria::OXYGEN)
+        );
+        assert_eq!(
+            trie.rating(Criteria::CO2).0,
+            search_for_rating(&report, &sorted, 0, Criteria::CO2)
+        );
+    }
+
+    #[test]
+    fn test_duplicate_lines() {
+        let report = Report::parse(["101", "101", "011"].into_iter()).unwrap();
+        let mut sorted = report.numbers.clone();
+        sorted.sort_unstable();
+        let trie = Trie::new(&report.numbers, report.width);
+
+        for criteria in [Criteria::OXYGEN, Criteria::CO2] {
+            assert_eq!(
+                trie.rating(criteria).0,
+                search_for_rating(&report, &sorted, 0, criteria)
+            );
+        }
+    }
+
+    #[test]
+    fn test_any_criteria_agree() {
+        use criteria::Keep;
+
+        let report = Report::parse(include_str!("input.txt").lines()).unwrap();
+        let mut sorted = report.numbers.clone();
+        sorted.sort_unstable();
+        let trie = Trie::new(&report.numbers, report.width);
+
+        for keep in [Keep::Majority, Keep::Minority] {
+            for on_tie in [false, true] {
+                let criteria = Criteria::new(keep, on_tie);
+
+                assert_eq!(
+                    trie.rating(criteria).0,
+                    search_for_rating(&report, &sorted, 0, criteria)
+                );
+            }
+        }
+    }
 }
@@ -0,0 +1,127 @@
+use crate::criteria::{BitCounts, Criteria};
+
+/// A node in the trie. `children[0]` holds the numbers with a 0 in the next
+/// column, `children[1]` the ones with a 1.
+#[derive(Debug, Default, Clone)]
+struct Node {
+    count: usize,
+    children: [Option<usize>; 2],
+}
+
+/// A binary trie of the numbers in a report, where each node knows how many
+/// numbers are below it. This means we can find out how common each bit is in
+/// a column without looking at all of the numbers.
+#[derive(Debug)]
+pub struct Trie {
+    // The nodes are kept in one Vec, and refer to each other by index
+    nodes: Vec<Node>,
+    width: usize,
+}
+
+impl Trie {
+    pub fn new(numbers: &[u128], width: usize) -> Self {
+        let mut trie = Self {
+            nodes: vec![Node::default()],
+            width,
+        };
+
+        for &num in numbers {
+            trie.insert(num);
+        }
+
+        trie
+    }
+
+    fn insert(&mut self, num: u128) {
+        let mut node = 0;
+        self.nodes[node].count += 1;
+
+        for column in 0..self.width {
+            let bit = ((num >> (self.width - 1 - column)) & 1) as usize;
+
+            node = match self.nodes[node].children[bit] {
+                Some(child) => child,
+                None => {
+                    self.nodes.push(Node::default());
+                    let child = self.nodes.len() - 1;
+                    self.nodes[node].children[bit] = Some(child);
+                    child
+                }
+            };
+
+            self.nodes[node].count += 1;
+        }
+    }
+
+    fn count(&self, child: Option<usize>) -> usize {
+        child.map_or(0, |c| self.nodes[c].count)
+    }
+
+    /// Finds a rating by walking down the trie, using the criteria to pick a
+    /// bit in each column from the numbers that are left. Once only one bit is
+    /// left in a column, that's the one that gets picked. Also returns the
+    /// counts of the numbers that were left in each column.
+    pub fn rating(&self, criteria: Criteria) -> (u128, Vec<BitCounts>) {
+        let mut node = 0;
+        let mut rating = 0;
+        let mut column_counts = Vec::with_capacity(self.width);
+
+        for _ in 0..self.width {
+            let [zeros, ones] = self.nodes[node].children;
+            let counts = BitCounts {
+                zeros: self.count(zeros),
+                ones: self.count(ones),
+            };
+
+            let bit = match counts {
+                BitCounts { ones: 0, .. } => false,
+                BitCounts { zeros: 0, .. } => true,
+                _ => criteria.pick(counts),
+            };
+
+            column_counts.push(counts);
+            rating = (rating << 1) | bit as u128;
+            node = self.nodes[node].children[bit as usize].unwrap();
+        }
+
+        (rating, column_counts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::criteria::Keep;
+
+    #[test]
+    fn test_counts() {
+        let trie = Trie::new(&[0b00, 0b01, 0b01, 0b11], 2);
+
+        assert_eq!(trie.nodes[0].count, 4);
+        assert_eq!(trie.count(trie.nodes[0].children[0]), 3);
+        assert_eq!(trie.count(trie.nodes[0].children[1]), 1);
+    }
+
+    #[test]
+    fn test_tie_breaking() {
+        let trie = Trie::new(&[0b10, 0b01], 2);
+
+        assert_eq!(trie.rating(Criteria::new(Keep::Majority, true)).0, 0b10);
+        assert_eq!(trie.rating(Criteria::new(Keep::Majority, false)).0, 0b01);
+    }
+
+    #[test]
+    fn test_rating_counts() {
+        let trie = Trie::new(&[0b00, 0b01, 0b01, 0b11], 2);
+        let (rating, counts) = trie.rating(Criteria::OXYGEN);
+
+        assert_eq!(rating, 0b01);
+        assert_eq!(
+            counts,
+            vec![
+                BitCounts { zeros: 3, ones: 1 },
+                BitCounts { zeros: 1, ones: 2 }
+            ]
+        );
+    }
+}
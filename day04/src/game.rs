@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+
+use crate::log::{Draw, Win};
+use crate::Board;
+
+/// A game of bingo. Instead of checking every number on every board for each
+/// draw, the game keeps an index of where each number is.
+#[derive(Debug)]
+pub struct Game {
+    pub boards: Vec<Board>,
+    /// Each number's (board, row, column) positions
+    index: HashMap<i32, Vec<(usize, usize, usize)>>,
+    won: Vec<bool>,
+    turns: usize,
+}
+
+impl Game {
+    pub fn new(boards: Vec<Board>) -> Self {
+        let mut index: HashMap<i32, Vec<(usize, usize, usize)>> = HashMap::new();
+
+        for (b, board) in boards.iter().enumerate() {
+            for (i, row) in board.rows.iter().enumerate() {
+                for (j, &(num, _)) in row.iter().enumerate() {
+                    index.entry(num).or_default().push((b, i, j));
+                }
+            }
+        }
+
+        Self {
+            won: vec![false; boards.len()],
+            boards,
+            index,
+            turns: 0,
+        }
+    }
+
+    /// Marks the number on every board that hasn't won yet. Returns which
+    /// boards were marked and which of them won, in order.
+    pub fn draw(&mut self, num: i32) -> Draw {
+        let mut marked = Vec::new();
+        let mut winners = Vec::new();
+
+        for &(b, i, j) in self.index.get(&num).into_iter().flatten() {
+            // Boards stop being marked once they've won, so their scores don't
+            // change
+            if self.won[b] {
+                continue;
+            }
+
+            marked.push(b);
+
+            if self.boards[b].mark(i, j) {
+                self.won[b] = true;
+                winners.push(b);
+            }
+        }
+
+        self.turns += 1;
+        marked.sort_unstable();
+        winners.sort_unstable();
+
+        Draw {
+            turn: self.turns,
+            number: num,
+            marked,
+            won: winners
+                .into_iter()
+                .map(|board| Win {
+                    board,
+                    score: self.boards[board].score(num),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_input;
+
+    #[test]
+    fn test_example() {
+        let (numbers, boards) = parse_input(include_str!("example_input.txt")).unwrap();
+        let mut game = Game::new(boards);
+
+        let winners: Vec<(usize, i32)> = numbers
+            .into_iter()
+            .flat_map(|num| game.draw(num).won.into_iter().map(move |w| (w.board, num)))
+            .collect();
+
+        assert_eq!(winners, vec![(2, 24), (0, 16), (1, 13)]);
+        assert_eq!(game.boards[2].score(24), 4512);
+        assert_eq!(game.boards[1].score(13), 1924);
+    }
+
+    #[test]
+    fn test_other_rules() {
+        use crate::rules::WinPattern;
+
+        let (numbers, mut boards) = parse_input(include_str!("example_input.txt")).unwrap();
+
+        for (i, board) in boards.iter_mut().enumerate() {
+            board.set_rule(&[WinPattern::Corners], i).unwrap();
+        }
+
+        let mut game = Game::new(boards);
+        let winners: Vec<(usize, i32)> = numbers
+            .into_iter()
+            .flat_map(|num| game.draw(num).won.into_iter().map(move |w| (w.board, num)))
+            .collect();
+
+        // Board 3's corners are 14, 4, 2 and 7
+        assert_eq!(winners[0], (2, 14));
+        assert_eq!(game.boards[2].score(14), 233 * 14);
+    }
+}
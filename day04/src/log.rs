@@ -0,0 +1,254 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::Game;
+
+const MARKED: &str = "\x1b[1;32m";
+const JUST_CALLED: &str = "\x1b[1;30;43m";
+const RESET: &str = "\x1b[0m";
+
+/// A board winning the game. Boards are numbered from 0, in the order they're
+/// in the input.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Win {
+    pub board: usize,
+    pub score: i32,
+}
+
+/// Everything that happened when a number was drawn
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Draw {
+    /// Turns start at 1
+    pub turn: usize,
+    pub number: i32,
+    /// The boards which had the number marked, including any that won
+    pub marked: Vec<usize>,
+    pub won: Vec<Win>,
+}
+
+/// A log of every draw in a game. It keeps the numbers on each board too, so a
+/// saved log can be replayed on its own.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GameLog {
+    pub boards: Vec<Vec<Vec<i32>>>,
+    pub draws: Vec<Draw>,
+}
+
+impl GameLog {
+    /// Draws all of the numbers, logging what happens
+    pub fn play(game: &mut Game, numbers: &[i32]) -> Self {
+        let boards = game
+            .boards
+            .iter()
+            .map(|board| {
+                board
+                    .rows
+                    .iter()
+                    .map(|row| row.iter().map(|&(num, _)| num).collect())
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            boards,
+            draws: numbers.iter().map(|&num| game.draw(num)).collect(),
+        }
+    }
+
+    /// Every win, in the order they happened
+    pub fn wins(&self) -> impl Iterator<Item = (&Draw, &Win)> {
+        self.draws
+            .iter()
+            .flat_map(|draw| draw.won.iter().map(move |win| (draw, win)))
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Loads a log saved by `to_json`, checking that it only mentions boards
+    /// it has
+    pub fn from_json(json: &str) -> Result<Self, Box<dyn Error>> {
+        let log: Self = serde_json::from_str(json)?;
+
+        let mentioned = log.draws.iter().flat_map(|draw| {
+            draw.marked
+                .iter()
+                .copied()
+                .chain(draw.won.iter().map(|win| win.board))
+        });
+
+        if let Some(board) = mentioned.into_iter().find(|&b| b >= log.boards.len()) {
+            return Err(format!("the log mentions board {}, which it doesn't have", board).into());
+        }
+
+        Ok(log)
+    }
+
+    /// Goes through the log again, showing the boards that each draw marked,
+    /// or just one board if `focus` is given. Marked numbers are highlighted,
+    /// and the number that was just called stands out.
+    pub fn replay(&self, focus: Option<usize>) -> String {
+        let mut marked = vec![HashSet::new(); self.boards.len()];
+        let mut output = String::new();
+
+        for draw in &self.draws {
+            let shown: Vec<usize> = draw
+                .marked
+                .iter()
+                .copied()
+                .filter(|&b| focus.is_none_or(|f| f == b))
+                .collect();
+
+            for &b in &draw.marked {
+                marked[b].insert(draw.number);
+            }
+
+            if focus.is_some() && shown.is_empty() {
+                continue;
+            }
+
+            writeln!(
+                output,
+                "Turn {}: drew {}, marked {} board(s)",
+                draw.turn,
+                draw.number,
+                draw.marked.len()
+            )
+            .unwrap();
+
+            for win in &draw.won {
+                writeln!(
+                    output,
+                    "  Board {} won with a score of {}",
+                    win.board, win.score
+                )
+                .unwrap();
+            }
+
+            for b in shown {
+                writeln!(output, "\nBoard {}:", b).unwrap();
+                output.push_str(&render_board(&self.boards[b], &marked[b], draw.number));
+            }
+
+            output.push('\n');
+        }
+
+        output
+    }
+}
+
+/// Draws a board with ANSI colours for the marked numbers
+fn render_board(board: &[Vec<i32>], marked: &HashSet<i32>, just_called: i32) -> String {
+    let width = board
+        .iter()
+        .flatten()
+        .map(|num| num.to_string().len())
+        .max()
+        .unwrap_or(0);
+
+    let mut output = String::new();
+
+    for row in board {
+        let cells: Vec<String> = row
+            .iter()
+            .map(|&num| {
+                if num == just_called {
+                    format!("{}{:>width$}{}", JUST_CALLED, num, RESET, width = width)
+                } else if marked.contains(&num) {
+                    format!("{}{:>width$}{}", MARKED, num, RESET, width = width)
+                } else {
+                    format!("{:>width$}", num, width = width)
+                }
+            })
+            .collect();
+
+        writeln!(output, "  {}", cells.join(" ")).unwrap();
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_input;
+
+    fn example_log() -> GameLog {
+        let (numbers, boards) = parse_input(include_str!("example_input.txt")).unwrap();
+        let mut game = Game::new(boards);
+
+        GameLog::play(&mut game, &numbers)
+    }
+
+    #[test]
+    fn test_log() {
+        let log = example_log();
+
+        assert_eq!(log.draws.len(), 27);
+        assert_eq!(
+            log.draws[0],
+            Draw {
+                turn: 1,
+                number: 7,
+                marked: vec![0, 1, 2],
+                won: vec![]
+            }
+        );
+
+        let wins: Vec<(usize, usize, i32)> = log
+            .wins()
+            .map(|(draw, win)| (draw.turn, win.board, win.score))
+            .collect();
+        assert_eq!(wins, vec![(12, 2, 4512), (14, 0, 2192), (15, 1, 1924)]);
+
+        // Boards that have won aren't marked any more
+        assert_eq!(log.draws[14].marked, vec![1]);
+    }
+
+    #[test]
+    fn test_json() {
+        let log = example_log();
+        let json: serde_json::Value = serde_json::from_str(&log.to_json().unwrap()).unwrap();
+
+        assert_eq!(json["draws"][11]["number"], 24);
+        assert_eq!(json["draws"][11]["won"][0]["score"], 4512);
+        assert_eq!(json["boards"][2][4][4], 7);
+    }
+
+    #[test]
+    fn test_replay_saved_log() {
+        let log = example_log();
+        let loaded = GameLog::from_json(&log.to_json().unwrap()).unwrap();
+
+        assert_eq!(loaded.boards, log.boards);
+        assert_eq!(loaded.draws, log.draws);
+        assert_eq!(loaded.replay(None), log.replay(None));
+
+        let bad_json =
+            r#"{"boards": [], "draws": [{"turn": 1, "number": 7, "marked": [0], "won": []}]}"#;
+        assert!(GameLog::from_json(bad_json).is_err());
+        assert!(GameLog::from_json("{").is_err());
+    }
+
+    #[test]
+    fn test_replay() {
+        let log = example_log();
+        let replay = log.replay(Some(2));
+
+        assert!(replay.starts_with("Turn 1: drew 7, marked 3 board(s)\n\nBoard 2:\n"));
+        assert!(replay.contains("  Board 2 won with a score of 4512"));
+        // The winning row of board 2, with 24 just called
+        assert!(replay.contains(&format!(
+            "{m}14{r} {m}21{r} {m}17{r} {c}24{r} {m} 4{r}",
+            m = MARKED,
+            c = JUST_CALLED,
+            r = RESET
+        )));
+        // Board 2 is never marked after it wins
+        assert!(!replay.contains("Turn 13"));
+    }
+}
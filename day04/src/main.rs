@@ -1,54 +1,189 @@
+use std::collections::HashSet;
+use std::env;
+use std::error::Error;
+use std::fmt::Display;
+use std::fs;
+
+use game::Game;
+use log::GameLog;
+use rules::{Line, WinPattern};
+use simulate::{simulate, Rank};
+
+mod game;
+mod log;
+mod rules;
+mod simulate;
+
+/// Boards are numbered from 0 in the order they're in the input, the same as
+/// in the game log, and rows from 1
+#[derive(Debug, PartialEq, Eq)]
+enum BingoError {
+    NoDraws,
+    InvalidNumber(String),
+    NotSquare {
+        board: usize,
+        row: usize,
+        len: usize,
+        size: usize,
+    },
+    DuplicateNumber {
+        board: usize,
+        number: i32,
+    },
+    InvalidPattern(String),
+    WrongMaskSize {
+        board: usize,
+        size: usize,
+        mask_size: usize,
+    },
+}
+
+impl Display for BingoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BingoError::NoDraws => write!(f, "there are no numbers to draw"),
+            BingoError::InvalidNumber(num) => write!(f, "{:?} is not a number", num),
+            BingoError::NotSquare {
+                board,
+                row,
+                len,
+                size,
+            } => write!(
+                f,
+                "board {}: row {} has {} numbers, but the board has {} rows",
+                board, row, len, size
+            ),
+            BingoError::DuplicateNumber { board, number } => {
+                write!(f, "board {}: {} appears more than once", board, number)
+            }
+            BingoError::InvalidPattern(pattern) => {
+                write!(f, "{:?} is not a winning pattern", pattern)
+            }
+            BingoError::WrongMaskSize {
+                board,
+                size,
+                mask_size,
+            } => write!(
+                f,
+                "board {}: the board is {}x{}, but the mask is {}x{}",
+                board, size, size, mask_size, mask_size
+            ),
+        }
+    }
+}
+
+impl Error for BingoError {}
+
+fn parse_number(input: &str) -> Result<i32, BingoError> {
+    input
+        .parse()
+        .map_err(|_| BingoError::InvalidNumber(input.to_string()))
+}
+
+/// A square bingo board of any size. Each number is stored with whether it
+/// has been marked.
+#[derive(Debug, Clone)]
 struct Board {
-    rows: [[(i32, bool); 5]; 5],
+    size: usize,
+    rows: Vec<Vec<(i32, bool)>>,
+    /// The lines that win the game, from the board's rule
+    lines: Vec<Line>,
+    /// Which lines go through each cell, indexed by `row * size + column`
+    cell_lines: Vec<Vec<usize>>,
+    /// How many numbers have been marked in each line
+    hits: Vec<usize>,
 }
 
 impl Board {
-    fn parse(lines: &[&str]) -> Self {
-        let mut board = Self {
-            rows: [[(-1, false); 5]; 5],
-        };
+    /// Parses a board from its rows. `board` is only used in error messages.
+    fn parse(lines: &[&str], board: usize) -> Result<Self, BingoError> {
+        let size = lines.len();
+        let mut seen = HashSet::new();
+        let mut rows = Vec::with_capacity(size);
 
-        for (i, line) in lines.iter().enumerate().take(5) {
-            let row_numbers = line.split_whitespace().map(str::parse).map(Result::unwrap);
+        for (i, line) in lines.iter().enumerate() {
+            let row: Vec<(i32, bool)> = line
+                .split_whitespace()
+                .map(|num| parse_number(num).map(|num| (num, false)))
+                .collect::<Result<_, _>>()?;
 
-            for (j, num) in row_numbers.into_iter().enumerate().take(5) {
-                board.rows[i][j].0 = num;
+            if row.len() != size {
+                return Err(BingoError::NotSquare {
+                    board,
+                    row: i + 1,
+                    len: row.len(),
+                    size,
+                });
             }
+
+            if let Some(&(number, _)) = row.iter().find(|&&(num, _)| !seen.insert(num)) {
+                return Err(BingoError::DuplicateNumber { board, number });
+            }
+
+            rows.push(row);
         }
 
-        board
+        let mut parsed = Self {
+            size,
+            rows,
+            lines: Vec::new(),
+            cell_lines: Vec::new(),
+            hits: Vec::new(),
+        };
+        parsed.set_rule(&WinPattern::DEFAULT_RULE, board)?;
+
+        Ok(parsed)
     }
 
-    fn mark_number(&mut self, num: i32) {
-        for i in 0..5 {
-            for j in 0..5 {
-                if self.rows[i][j].0 == num {
-                    self.rows[i][j].1 = true;
+    /// Changes which patterns win the game. Any numbers that have already been
+    /// marked count towards the new lines. `board` is only used in error
+    /// messages.
+    fn set_rule(&mut self, rule: &[WinPattern], board: usize) -> Result<(), BingoError> {
+        if let Some(mask_size) = rule
+            .iter()
+            .filter_map(WinPattern::required_size)
+            .find(|&mask_size| mask_size != self.size)
+        {
+            return Err(BingoError::WrongMaskSize {
+                board,
+                size: self.size,
+                mask_size,
+            });
+        }
+
+        self.lines = rule.iter().flat_map(|p| p.lines(self.size)).collect();
+        self.cell_lines = vec![Vec::new(); self.size * self.size];
+        self.hits = vec![0; self.lines.len()];
+
+        for (l, line) in self.lines.iter().enumerate() {
+            for &(i, j) in line {
+                self.cell_lines[i * self.size + j].push(l);
+
+                if self.rows[i][j].1 {
+                    self.hits[l] += 1;
                 }
             }
         }
-    }
 
-    fn marked_rows(&self) -> [[bool; 5]; 5] {
-        self.rows.map(|row| row.map(|(_, marked)| marked))
+        Ok(())
     }
 
-    fn marked_columns(&self) -> [[bool; 5]; 5] {
-        [0, 1, 2, 3, 4].map(|i| self.rows.map(|row| row[i].1))
-    }
+    /// Marks the number at this position. Returns true if this completed any
+    /// of the board's lines.
+    fn mark(&mut self, row: usize, column: usize) -> bool {
+        if self.rows[row][column].1 {
+            return false;
+        }
 
-    fn has_won(&self) -> bool {
-        let completed_row = self
-            .marked_rows()
-            .iter()
-            .any(|row| row.iter().copied().all(|m| m));
+        self.rows[row][column].1 = true;
+        let mut won = false;
 
-        let completed_col = self
-            .marked_columns()
-            .iter()
-            .any(|col| col.iter().copied().all(|m| m));
+        for &l in &self.cell_lines[row * self.size + column] {
+            self.hits[l] += 1;
+            won |= self.hits[l] == self.lines[l].len();
+        }
 
-        completed_row || completed_col
+        won
     }
 
     fn score(&self, just_called: i32) -> i32 {
@@ -64,33 +199,226 @@ impl Board {
     }
 }
 
-fn main() {
-    let mut lines = include_str!("input.txt").lines();
+/// Parses the numbers to draw from the first line, then the boards, which are
+/// separated by blank lines
+fn parse_input(input: &str) -> Result<(Vec<i32>, Vec<Board>), BingoError> {
+    let mut lines = input.lines();
 
     let numbers: Vec<i32> = lines
         .next()
-        .unwrap()
+        .filter(|l| !l.trim().is_empty())
+        .ok_or(BingoError::NoDraws)?
         .split(',')
-        .map(str::parse)
-        .map(Result::unwrap)
-        .collect();
+        .map(|num| parse_number(num.trim()))
+        .collect::<Result<_, _>>()?;
+
+    let lines: Vec<&str> = lines.collect();
+
+    let boards = lines
+        .split(|l| l.trim().is_empty())
+        .filter(|board_lines| !board_lines.is_empty())
+        .enumerate()
+        .map(|(i, board_lines)| Board::parse(board_lines, i))
+        .collect::<Result<_, _>>()?;
+
+    Ok((numbers, boards))
+}
 
-    let lines: Vec<&str> = lines.filter(|&l| !l.is_empty()).collect();
-    // On nightly there's an array_chunks() method that would have worked nicely
-    let mut boards: Vec<Board> = lines.chunks(5).map(Board::parse).collect();
-    let mut finished_boards: Vec<(Board, i32)> = Vec::with_capacity(boards.len());
+/// Prints each board's chance of winning over lots of random games, with the
+/// most likely boards first
+fn print_simulation(boards: &[Board], numbers: &[i32], trials: usize, seed: u64, rank: Rank) {
+    let stats = simulate(boards, numbers, trials, seed);
+    let mut order: Vec<usize> = (0..boards.len()).collect();
+    order.sort_by(|&a, &b| {
+        stats[b]
+            .probability(rank, trials)
+            .total_cmp(&stats[a].probability(rank, trials))
+    });
+
+    println!("{} games with seed {}:", trials, seed);
+    println!("  Board  P(first)  P(last)  Turn");
+
+    for b in order {
+        let turn = stats[b]
+            .expected_turn()
+            .map_or("-".to_string(), |t| format!("{:.1}", t));
+
+        println!(
+            "  {:>5}  {:>8.3}  {:>7.3}  {:>4}",
+            b,
+            stats[b].probability(Rank::First, trials),
+            stats[b].probability(Rank::Last, trials),
+            turn
+        );
+    }
+}
 
-    for num in numbers {
-        boards.iter_mut().for_each(|b| b.mark_number(num));
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let mut rule = WinPattern::DEFAULT_RULE.to_vec();
+    let mut log_path = None;
+    let mut replay = None;
+    let mut saved_log = None;
+    let mut trials = None;
+    let mut seed = 0;
+    let mut rank = Rank::First;
 
-        while let Some(board_idx) = boards.iter().position(|b| b.has_won()) {
-            finished_boards.push((boards.remove(board_idx), num));
+    // e.g. `cargo run -p day04 -- --rule rows,columns,diagonals --log log.json`
+    // `--replay` takes a board number, or `all`. `--replay-log <path>` replays a
+    // saved log instead of playing, showing every board unless `--replay` picks
+    // one. `--simulate <trials>` plays lots of shuffled games instead, with
+    // `--seed` and `--rank first|last`
+    for arg_pair in args.chunks(2) {
+        match arg_pair {
+            [flag, patterns] if flag == "--rule" => rule = WinPattern::parse_rule(patterns)?,
+            [flag, path] if flag == "--log" => log_path = Some(path),
+            [flag, path] if flag == "--replay-log" => saved_log = Some(path),
+            [flag, board] if flag == "--replay" && board == "all" => replay = Some(None),
+            [flag, board] if flag == "--replay" => replay = Some(Some(board.parse()?)),
+            [flag, n] if flag == "--simulate" => match n.parse()? {
+                0 => return Err("--simulate needs at least 1 game".into()),
+                n => trials = Some(n),
+            },
+            [flag, n] if flag == "--seed" => seed = n.parse()?,
+            [flag, by] if flag == "--rank" && by == "first" => rank = Rank::First,
+            [flag, by] if flag == "--rank" && by == "last" => rank = Rank::Last,
+            _ => return Err(format!("Unexpected arguments: {}", arg_pair.join(" ")).into()),
         }
     }
 
-    let (task1_board, task1_num) = &finished_boards[0];
-    let (task2_board, task2_num) = &finished_boards[finished_boards.len() - 1];
+    if let Some(path) = saved_log {
+        let log = GameLog::from_json(&fs::read_to_string(path)?)?;
+        print!("{}", log.replay(replay.flatten()));
 
-    println!("Task 1 Answer: {}", task1_board.score(*task1_num));
-    println!("Task 2 Answer: {}", task2_board.score(*task2_num));
+        return Ok(());
+    }
+
+    let (numbers, mut boards) = parse_input(include_str!("input.txt"))?;
+
+    for (i, board) in boards.iter_mut().enumerate() {
+        board.set_rule(&rule, i)?;
+    }
+
+    if let Some(trials) = trials {
+        print_simulation(&boards, &numbers, trials, seed, rank);
+        return Ok(());
+    }
+
+    let mut game = Game::new(boards);
+    let log = GameLog::play(&mut game, &numbers);
+
+    if let Some(path) = log_path {
+        fs::write(path, log.to_json()?)?;
+    }
+
+    if let Some(focus) = replay {
+        print!("{}", log.replay(focus));
+    }
+
+    // With some rules it's possible that no board ever wins
+    let (Some((_, first)), Some((_, last))) = (log.wins().next(), log.wins().last()) else {
+        println!("No board won");
+        return Ok(());
+    };
+
+    println!("Task 1 Answer: {}", first.score);
+    println!("Task 2 Answer: {}", last.score);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_example() {
+        let (numbers, boards) = parse_input(include_str!("example_input.txt")).unwrap();
+
+        assert_eq!(numbers.len(), 27);
+        assert_eq!(boards.len(), 3);
+        assert!(boards.iter().all(|b| b.size == 5));
+        assert_eq!(boards[2].rows[4][4], (7, false));
+    }
+
+    #[test]
+    fn test_other_sizes() {
+        let input = "1,5,3\n\n1 2\n3 4\n\n\n\n5 6 7\n8 9 10\n11 12 13\n";
+        let (_, mut boards) = parse_input(input).unwrap();
+
+        assert_eq!(boards.len(), 2);
+        assert_eq!((boards[0].size, boards[1].size), (2, 3));
+
+        assert!(!boards[0].mark(0, 0));
+        assert!(boards[0].mark(1, 0));
+        // Marking the same number twice doesn't count
+        assert!(!boards[0].mark(1, 0));
+        assert_eq!(boards[0].score(3), (2 + 4) * 3);
+    }
+
+    #[test]
+    fn test_not_square() {
+        let error = parse_input("1\n\n1 2\n3 4\n\n1 2 3\n4 5 6\n").unwrap_err();
+
+        assert_eq!(
+            error,
+            BingoError::NotSquare {
+                board: 1,
+                row: 1,
+                len: 3,
+                size: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_duplicate_number() {
+        let error = parse_input("1\n\n1 2\n3 1\n").unwrap_err();
+
+        assert_eq!(
+            error,
+            BingoError::DuplicateNumber {
+                board: 0,
+                number: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_rules() {
+        let (_, mut boards) = parse_input("1\n\n1 2 3\n4 5 6\n7 8 9\n").unwrap();
+        let board = &mut boards[0];
+
+        board.mark(0, 0);
+        board.mark(1, 1);
+        assert!(!board.mark(1, 2));
+
+        // Numbers that are already marked count towards the new rule
+        board.set_rule(&[WinPattern::Diagonals], 0).unwrap();
+        assert!(board.mark(2, 2));
+        assert_eq!(board.score(9), (2 + 3 + 4 + 7 + 8) * 9);
+
+        board.set_rule(&[WinPattern::Blackout], 0).unwrap();
+        assert!(!board.mark(2, 0));
+
+        assert_eq!(
+            board
+                .set_rule(&[WinPattern::parse("mask:#./.#").unwrap()], 0)
+                .unwrap_err(),
+            BingoError::WrongMaskSize {
+                board: 0,
+                size: 3,
+                mask_size: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert_eq!(parse_input("").unwrap_err(), BingoError::NoDraws);
+        assert_eq!(
+            parse_input("1,x\n").unwrap_err(),
+            BingoError::InvalidNumber("x".to_string())
+        );
+    }
 }
@@ -0,0 +1,141 @@
+use crate::BingoError;
+
+/// A set of cells which wins the game once they're all marked
+pub type Line = Vec<(usize, usize)>;
+
+/// A kind of winning pattern. Each pattern can make several lines on a board,
+/// e.g. `Rows` makes one line for each row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WinPattern {
+    Rows,
+    Columns,
+    Diagonals,
+    Corners,
+    /// Every number on the board
+    Blackout,
+    /// A custom pattern, where `true` marks the cells that need to be marked.
+    /// It has to be the same size as the board.
+    Mask(Vec<Vec<bool>>),
+}
+
+impl WinPattern {
+    /// The rules from the puzzle
+    pub const DEFAULT_RULE: [WinPattern; 2] = [WinPattern::Rows, WinPattern::Columns];
+
+    /// Parses a pattern name, or a mask like `mask:#...#/.#.#./..#../.#.#./#...#`
+    /// where each `/` starts a new row and `#` is a cell that needs marking
+    pub fn parse(input: &str) -> Result<Self, BingoError> {
+        let pattern = match input {
+            "rows" => Self::Rows,
+            "columns" => Self::Columns,
+            "diagonals" => Self::Diagonals,
+            "corners" => Self::Corners,
+            "blackout" => Self::Blackout,
+            _ => {
+                let mask = input
+                    .strip_prefix("mask:")
+                    .ok_or_else(|| BingoError::InvalidPattern(input.to_string()))?;
+
+                let rows: Vec<Vec<bool>> = mask
+                    .split('/')
+                    .map(|row| row.chars().map(|c| c == '#').collect())
+                    .collect();
+
+                let is_square = rows.iter().all(|row| row.len() == rows.len());
+                let has_cells = rows.iter().flatten().any(|&c| c);
+
+                if !is_square || !has_cells {
+                    return Err(BingoError::InvalidPattern(input.to_string()));
+                }
+
+                Self::Mask(rows)
+            }
+        };
+
+        Ok(pattern)
+    }
+
+    /// Parses a comma separated list of patterns
+    pub fn parse_rule(input: &str) -> Result<Vec<Self>, BingoError> {
+        input.split(',').map(Self::parse).collect()
+    }
+
+    /// The size of board this pattern works on, if it only works on one size
+    pub fn required_size(&self) -> Option<usize> {
+        match self {
+            Self::Mask(rows) => Some(rows.len()),
+            _ => None,
+        }
+    }
+
+    /// All of the lines this pattern makes on a board of this size
+    pub fn lines(&self, size: usize) -> Vec<Line> {
+        let all_cells = || (0..size).flat_map(move |i| (0..size).map(move |j| (i, j)));
+
+        match self {
+            Self::Rows => (0..size)
+                .map(|i| (0..size).map(|j| (i, j)).collect())
+                .collect(),
+            Self::Columns => (0..size)
+                .map(|j| (0..size).map(|i| (i, j)).collect())
+                .collect(),
+            Self::Diagonals => vec![
+                (0..size).map(|i| (i, i)).collect(),
+                (0..size).map(|i| (i, size - 1 - i)).collect(),
+            ],
+            Self::Corners => {
+                let mut corners = vec![(0, 0), (0, size - 1), (size - 1, 0), (size - 1, size - 1)];
+                // On tiny boards some of the corners are the same cell
+                corners.sort_unstable();
+                corners.dedup();
+
+                vec![corners]
+            }
+            Self::Blackout => vec![all_cells().collect()],
+            Self::Mask(rows) => {
+                assert_eq!(rows.len(), size, "Mask is the wrong size for the board");
+
+                vec![all_cells().filter(|&(i, j)| rows[i][j]).collect()]
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines() {
+        assert_eq!(
+            WinPattern::Rows.lines(2),
+            vec![vec![(0, 0), (0, 1)], vec![(1, 0), (1, 1)]]
+        );
+        assert_eq!(
+            WinPattern::Diagonals.lines(3),
+            vec![vec![(0, 0), (1, 1), (2, 2)], vec![(0, 2), (1, 1), (2, 0)]]
+        );
+        assert_eq!(
+            WinPattern::Corners.lines(5),
+            vec![vec![(0, 0), (0, 4), (4, 0), (4, 4)]]
+        );
+        assert_eq!(WinPattern::Corners.lines(1), vec![vec![(0, 0)]]);
+        assert_eq!(WinPattern::Blackout.lines(3)[0].len(), 9);
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            WinPattern::parse_rule("rows,diagonals").unwrap(),
+            vec![WinPattern::Rows, WinPattern::Diagonals]
+        );
+
+        let mask = WinPattern::parse("mask:#.#/.../#.#").unwrap();
+        assert_eq!(mask.required_size(), Some(3));
+        assert_eq!(mask.lines(3), WinPattern::Corners.lines(3));
+
+        assert!(WinPattern::parse("zigzag").is_err());
+        assert!(WinPattern::parse("mask:#./..#").is_err());
+        assert!(WinPattern::parse("mask:../..").is_err());
+    }
+}
@@ -0,0 +1,131 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+use crate::game::Game;
+use crate::Board;
+
+/// Which winner to rank the boards by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rank {
+    First,
+    Last,
+}
+
+/// How one board did over all of the games
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BoardStats {
+    /// Boards that win on the same draw all count as winning first (or last)
+    pub first: usize,
+    pub last: usize,
+    /// How many games the board won at all, and the total of the turns it won on
+    pub wins: usize,
+    pub total_turns: usize,
+}
+
+impl BoardStats {
+    pub fn probability(&self, rank: Rank, trials: usize) -> f64 {
+        let count = match rank {
+            Rank::First => self.first,
+            Rank::Last => self.last,
+        };
+
+        count as f64 / trials as f64
+    }
+
+    /// The average turn the board won on, out of the games it won
+    pub fn expected_turn(&self) -> Option<f64> {
+        (self.wins > 0).then(|| self.total_turns as f64 / self.wins as f64)
+    }
+}
+
+/// Plays the game many times with the numbers shuffled into a random order,
+/// keeping track of when each board wins. The same seed always gives the same
+/// results.
+pub fn simulate(boards: &[Board], numbers: &[i32], trials: usize, seed: u64) -> Vec<BoardStats> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut stats = vec![BoardStats::default(); boards.len()];
+    let mut order = numbers.to_vec();
+
+    for _ in 0..trials {
+        order.shuffle(&mut rng);
+
+        let mut game = Game::new(boards.to_vec());
+        let mut winners: Vec<Vec<usize>> = Vec::new();
+        let mut won = 0;
+
+        for &num in &order {
+            let draw = game.draw(num);
+
+            if draw.won.is_empty() {
+                continue;
+            }
+
+            for win in &draw.won {
+                stats[win.board].wins += 1;
+                stats[win.board].total_turns += draw.turn;
+            }
+
+            won += draw.won.len();
+            winners.push(draw.won.into_iter().map(|w| w.board).collect());
+
+            // Nothing else can happen once every board has won
+            if won == boards.len() {
+                break;
+            }
+        }
+
+        for &b in winners.first().into_iter().flatten() {
+            stats[b].first += 1;
+        }
+        for &b in winners.last().into_iter().flatten() {
+            stats[b].last += 1;
+        }
+    }
+
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_input;
+
+    #[test]
+    fn test_same_seed() {
+        let (numbers, boards) = parse_input(include_str!("example_input.txt")).unwrap();
+
+        assert_eq!(
+            simulate(&boards, &numbers, 100, 1),
+            simulate(&boards, &numbers, 100, 1)
+        );
+    }
+
+    #[test]
+    fn test_stats() {
+        let (numbers, boards) = parse_input(include_str!("example_input.txt")).unwrap();
+        let stats = simulate(&boards, &numbers, 500, 4);
+
+        // Every number gets drawn, so every board wins every game
+        assert!(stats.iter().all(|s| s.wins == 500));
+        assert!(stats.iter().map(|s| s.first).sum::<usize>() >= 500);
+        assert!(stats.iter().map(|s| s.last).sum::<usize>() >= 500);
+
+        for s in &stats {
+            let turn = s.expected_turn().unwrap();
+            assert!((5.0..=27.0).contains(&turn));
+        }
+    }
+
+    #[test]
+    fn test_certain_winner() {
+        // Both of the second board's lines need 1, which is all the first
+        // board needs
+        let (numbers, boards) = parse_input("1,2,3\n\n1\n\n1 2\n3 4\n").unwrap();
+        let stats = simulate(&boards, &numbers, 50, 0);
+
+        assert_eq!(stats[0].probability(Rank::First, 50), 1.0);
+        assert_eq!(stats[1].probability(Rank::Last, 50), 1.0);
+        assert!(stats[1].expected_turn() > stats[0].expected_turn());
+    }
+}
@@ -0,0 +1,135 @@
+use std::error::Error;
+use std::fmt::Display;
+
+use crate::vec2::{Bounds, Vec2};
+use crate::Grid;
+
+/// The most pixels an image can have, which is 4096x4096. Lines with huge
+/// coordinates would need terabytes otherwise.
+const MAX_PIXELS: usize = 1 << 24;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ImageTooLarge {
+    width: usize,
+    height: usize,
+}
+
+impl Display for ImageTooLarge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "A {}x{} image is too large, the limit is {} pixels",
+            self.width, self.height, MAX_PIXELS
+        )
+    }
+}
+
+impl Error for ImageTooLarge {}
+
+/// Builds a binary PGM/PPM image with one channel for each grid, so one grid
+/// makes a greyscale image and three make a colour one. Each channel is
+/// scaled so its most covered point is at full brightness.
+fn render(magic: &str, grids: &[&Grid]) -> Result<Vec<u8>, ImageTooLarge> {
+    let bounds = Bounds::from_points(grids.iter().flat_map(|g| g.coverage.keys().copied()))
+        .unwrap_or(Bounds::new(Vec2::default(), Vec2::default()));
+    let (width, height) = (bounds.width(), bounds.height());
+    let min = bounds.min;
+
+    let pixels = width
+        .checked_mul(height)
+        .filter(|&pixels| pixels <= MAX_PIXELS)
+        .ok_or(ImageTooLarge { width, height })?;
+
+    let mut image = format!("{}\n{} {}\n255\n", magic, width, height).into_bytes();
+    let header_len = image.len();
+    image.resize(header_len + pixels * grids.len(), 0);
+
+    for (channel, grid) in grids.iter().enumerate() {
+        let brightest = grid.coverage.values().copied().max().unwrap_or(1);
+
+        for (point, &count) in &grid.coverage {
+            let pixel = (point.y - min.y) as usize * width + (point.x - min.x) as usize;
+
+            image[header_len + pixel * grids.len() + channel] = (count * 255 / brightest) as u8;
+        }
+    }
+
+    Ok(image)
+}
+
+/// A greyscale PGM image of how many lines cover each point
+pub fn to_pgm(grid: &Grid) -> Result<Vec<u8>, ImageTooLarge> {
+    render("P5", &[grid])
+}
+
+/// A colour PPM image with each category of line in its own colour:
+/// orthogonal lines are red, diagonals green and any other slopes blue
+pub fn to_ppm(orthogonal: &Grid, diagonal: &Grid, other: &Grid) -> Result<Vec<u8>, ImageTooLarge> {
+    render("P6", &[orthogonal, diagonal, other])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Line, Raster};
+
+    fn grid(lines: &[&str]) -> Grid {
+        let mut grid = Grid::new(Raster::Lattice);
+
+        for line in lines {
+            grid.apply_line(&Line::parse(line));
+        }
+
+        grid
+    }
+
+    #[test]
+    fn test_pgm() {
+        let image = to_pgm(&grid(&["1,1 -> 3,1", "2,0 -> 2,2"])).unwrap();
+        let header = b"P5\n3 3\n255\n";
+
+        assert_eq!(&image[..header.len()], header);
+        assert_eq!(
+            &image[header.len()..],
+            &[0, 127, 0, 127, 255, 127, 0, 127, 0]
+        );
+    }
+
+    #[test]
+    fn test_ppm() {
+        let image = to_ppm(&grid(&["0,0 -> 1,0"]), &grid(&["0,0 -> 1,1"]), &grid(&[])).unwrap();
+        let header = b"P6\n2 2\n255\n";
+
+        assert_eq!(&image[..header.len()], header);
+        assert_eq!(
+            &image[header.len()..],
+            &[255, 255, 0, 255, 0, 0, 0, 0, 0, 0, 255, 0]
+        );
+    }
+
+    #[test]
+    fn test_negative_coordinates() {
+        let image = to_pgm(&grid(&["-2,-1 -> -1,-1"])).unwrap();
+
+        assert!(image.starts_with(b"P5\n2 1\n255\n"));
+    }
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(to_pgm(&grid(&[])).unwrap(), b"P5\n1 1\n255\n\0");
+    }
+
+    #[test]
+    fn test_too_large() {
+        // Only the two corners are covered, but the image would be 5000001x3000001
+        let grid = grid(&["0,0 -> 0,0", "5000000,3000000 -> 5000000,3000000"]);
+
+        assert_eq!(
+            to_pgm(&grid),
+            Err(ImageTooLarge {
+                width: 5000001,
+                height: 3000001
+            })
+        );
+    }
+}
@@ -1,27 +1,20 @@
-use derive_more::{AddAssign, Sub};
-
-use std::cmp::max;
-use std::collections::HashSet;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::error::Error;
+use std::fmt::Display;
+use std::fs;
 use std::iter;
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, AddAssign, Sub)]
-struct Vec2 {
-    x: i32,
-    y: i32,
-}
-
-impl Vec2 {
-    fn new(x: i32, y: i32) -> Self {
-        Self { x, y }
-    }
-
-    fn parse(input: &str) -> Self {
-        let mut nums = input.split(',').map(str::parse).map(Result::unwrap);
+use vec2::Vec2;
 
-        Self::new(nums.next().unwrap(), nums.next().unwrap())
-    }
-}
+mod heatmap;
+mod sweep;
+// This is meant to be reusable, so the puzzle doesn't need all of it
+#[allow(dead_code)]
+mod vec2;
 
+#[derive(Clone)]
 struct Line {
     a: Vec2,
     b: Vec2,
@@ -29,7 +22,7 @@ struct Line {
 
 impl Line {
     fn parse(input: &str) -> Self {
-        let mut points = input.split(" -> ").map(Vec2::parse);
+        let mut points = input.split(" -> ").map(|p| p.parse().unwrap());
 
         Self {
             a: points.next().unwrap(),
@@ -42,79 +35,478 @@ impl Line {
         self.a.x == self.b.x || self.a.y == self.b.y
     }
 
-    // A unit vector of the line's direction
+    fn category(&self) -> Category {
+        let dir = self.b - self.a;
+
+        if self.is_orthogonal() {
+            Category::Orthogonal
+        } else if dir.x.abs() == dir.y.abs() {
+            Category::Diagonal
+        } else {
+            Category::Other
+        }
+    }
+
+    // The smallest step between two lattice points on the line
     fn direction(&self) -> Vec2 {
         let dir = self.b - self.a;
+        let divisor = gcd(dir.x.abs(), dir.y.abs()).max(1);
 
-        // We know the lines will only be right angles or diagonals
-        Vec2::new(dir.x.signum(), dir.y.signum())
+        Vec2::new(dir.x / divisor, dir.y / divisor)
     }
 
-    // The number of points on the line
+    // The number of lattice points on the line
     fn length(&self) -> usize {
         let dir = self.b - self.a;
 
-        max(dir.x.abs(), dir.y.abs()) as usize + 1
+        gcd(dir.x.abs(), dir.y.abs()) as usize + 1
+    }
+
+    // An iterator of all the points on the line, from a to b
+    fn points(&self, raster: Raster) -> Box<dyn Iterator<Item = Vec2> + '_> {
+        match raster {
+            Raster::Lattice => Box::new(self.lattice_points()),
+            Raster::Supercover => Box::new(self.supercover_points()),
+        }
     }
 
-    // An iterator of all the points on the line
-    fn points(&self) -> impl Iterator<Item = Vec2> + '_ {
+    // Just the points that are exactly on the line
+    fn lattice_points(&self) -> impl Iterator<Item = Vec2> + '_ {
         let mut current_pos = self.a;
+        let direction = self.direction();
 
         iter::repeat_with(move || {
             let old_pos = current_pos;
-            current_pos += self.direction();
+            current_pos += direction;
 
             old_pos
         })
         .take(self.length())
     }
+
+    // Every point whose square the line goes through, treating each point as
+    // the centre of a 1x1 square. Where the line passes exactly through a
+    // corner it also touches the two squares on either side, so this is only
+    // the same as the lattice points for right angles.
+    fn supercover_points(&self) -> impl Iterator<Item = Vec2> + '_ {
+        let dir = self.b - self.a;
+        let (dx, dy) = (dir.x.abs() as i64, dir.y.abs() as i64);
+        let step = Vec2::new(dir.x.signum(), dir.y.signum());
+
+        let mut current_pos = self.a;
+        // How many squares we've moved along each axis
+        let (mut ix, mut iy) = (0, 0);
+        // The squares beside a corner, which come before the diagonal step
+        let mut beside_corner = Vec::new();
+        let mut done = false;
+
+        iter::from_fn(move || {
+            if let Some(pos) = beside_corner.pop() {
+                return Some(pos);
+            }
+
+            if done {
+                return None;
+            }
+
+            let old_pos = current_pos;
+
+            if ix == dx && iy == dy {
+                done = true;
+                return Some(old_pos);
+            }
+
+            // Compare where the line crosses the next vertical and horizontal
+            // square edges, scaled up to stay in integers
+            match ((1 + 2 * ix) * dy).cmp(&((1 + 2 * iy) * dx)) {
+                Ordering::Less => {
+                    current_pos.x += step.x;
+                    ix += 1;
+                }
+                Ordering::Greater => {
+                    current_pos.y += step.y;
+                    iy += 1;
+                }
+                Ordering::Equal => {
+                    beside_corner.push(old_pos + Vec2::new(0, step.y));
+                    beside_corner.push(old_pos + Vec2::new(step.x, 0));
+                    current_pos += step;
+                    ix += 1;
+                    iy += 1;
+                }
+            }
+
+            Some(old_pos)
+        })
+    }
+}
+
+/// How to turn a line into points
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Raster {
+    Lattice,
+    Supercover,
+}
+
+fn gcd(a: i32, b: i32) -> i32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// What kind of line something is, so lines can be looked at separately
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Category {
+    Orthogonal,
+    Diagonal,
+    /// Any other slope
+    Other,
+}
+
+impl Category {
+    fn parse(input: &str) -> Option<Self> {
+        match input {
+            "orthogonal" => Some(Self::Orthogonal),
+            "diagonal" => Some(Self::Diagonal),
+            "other" => Some(Self::Other),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Category {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Orthogonal => "orthogonal",
+            Self::Diagonal => "diagonal",
+            Self::Other => "other",
+        };
+
+        write!(f, "{}", name)
+    }
 }
 
+/// How many lines cover each point
 struct Grid {
-    seen_points: HashSet<Vec2>,
-    output_points: HashSet<Vec2>,
+    raster: Raster,
+    coverage: HashMap<Vec2, usize>,
 }
 
 impl Grid {
-    fn new() -> Self {
+    fn new(raster: Raster) -> Self {
         Self {
-            seen_points: HashSet::new(),
-            output_points: HashSet::new(),
+            raster,
+            coverage: HashMap::new(),
         }
     }
 
     fn apply_line(&mut self, line: &Line) {
-        for point in line.points() {
-            if !self.seen_points.insert(point) {
-                // The point was already in seen_points
-                self.output_points.insert(point);
-            }
+        for point in line.points(self.raster) {
+            *self.coverage.entry(point).or_default() += 1;
         }
     }
 
+    /// The number of points covered by at least k lines
+    fn at_least(&self, k: usize) -> usize {
+        self.coverage.values().filter(|&&count| count >= k).count()
+    }
+
+    /// How many points are covered by each number of lines
+    fn histogram(&self) -> BTreeMap<usize, usize> {
+        let mut histogram = BTreeMap::new();
+
+        for &count in self.coverage.values() {
+            *histogram.entry(count).or_default() += 1;
+        }
+
+        histogram
+    }
+
+    /// The point covered by the most lines. If there's a tie, it's the one
+    /// nearest the top left, going along each row first.
+    fn most_dangerous(&self) -> Option<(Vec2, usize)> {
+        self.coverage
+            .iter()
+            .max_by_key(|&(point, &count)| (count, Reverse((point.y, point.x))))
+            .map(|(&point, &count)| (point, count))
+    }
+
     fn output(&self) -> usize {
-        self.output_points.len()
+        self.at_least(2)
     }
 }
 
-fn main() {
-    let (orthogonal_lines, diagonal_lines): (Vec<Line>, Vec<Line>) = include_str!("input.txt")
-        .lines()
-        .map(Line::parse)
-        .partition(|l| l.is_orthogonal());
+/// Counts the points where at least two lines overlap. The sweep doesn't need
+/// to go through every point, but it only works with lattice points for right
+/// angles and diagonals.
+fn count_overlaps(lines: &[&Line], raster: Raster) -> usize {
+    if raster == Raster::Lattice {
+        if let Some(count) = sweep::count_overlaps(lines.iter().copied()) {
+            return count;
+        }
+    }
 
-    let mut grid = Grid::new();
+    let mut grid = Grid::new(raster);
 
-    for line in orthogonal_lines {
-        grid.apply_line(&line);
+    for line in lines {
+        grid.apply_line(line);
     }
 
-    println!("Task 1: {}", grid.output());
+    grid.output()
+}
 
-    for line in diagonal_lines {
-        grid.apply_line(&line);
+/// A grid of the lines in a category, or all lines if there's no category
+fn category_grid(lines: &[Line], raster: Raster, category: Option<Category>) -> Grid {
+    let mut grid = Grid::new(raster);
+
+    for line in lines
+        .iter()
+        .filter(|l| category.is_none_or(|c| l.category() == c))
+    {
+        grid.apply_line(line);
+    }
+
+    grid
+}
+
+/// Prints how much the lines in a category cover, or all lines if there's no
+/// category
+fn print_coverage(lines: &[Line], raster: Raster, category: Option<Category>, k: usize) {
+    let grid = category_grid(lines, raster, category);
+
+    match category {
+        Some(category) => println!("Coverage of {} lines:", category),
+        None => println!("Coverage of all lines:"),
+    }
+
+    println!("  Lines  Points");
+
+    for (count, points) in grid.histogram() {
+        println!("  {:>5}  {:>6}", count, points);
+    }
+
+    println!(
+        "Points covered by at least {} lines: {}",
+        k,
+        grid.at_least(k)
+    );
+
+    if let Some((point, count)) = grid.most_dangerous() {
+        println!(
+            "Most dangerous point: {},{} ({} lines)",
+            point.x, point.y, count
+        );
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut raster = Raster::Lattice;
+    let mut coverage = None;
+    let mut k = 2;
+    let mut heatmap = None;
+    let mut colour_heatmap = None;
+
+    // The puzzle counts the lattice points on each line, but
+    // `cargo run -p day05 -- --supercover` counts every square a line goes
+    // through instead, including both sides of a corner. To look at how much the lines
+    // cover, use `--coverage <all|orthogonal|diagonal|other>` and optionally
+    // `--at-least <k>`. `--heatmap <path>` saves a greyscale PGM image of the
+    // vents, and `--colour-heatmap <path>` a PPM image with a colour for each
+    // category
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--supercover" => raster = Raster::Supercover,
+            "--coverage" => {
+                let category = args.next().ok_or("Missing category")?;
+                coverage = Some(match category.as_str() {
+                    "all" => None,
+                    _ => Some(Category::parse(&category).ok_or("Unknown category")?),
+                });
+            }
+            "--at-least" => k = args.next().ok_or("Missing count")?.parse()?,
+            "--heatmap" => heatmap = Some(args.next().ok_or("Missing path")?),
+            "--colour-heatmap" => colour_heatmap = Some(args.next().ok_or("Missing path")?),
+            _ => return Err(format!("Unexpected argument: {}", arg).into()),
+        }
+    }
+
+    let lines: Vec<Line> = include_str!("input.txt").lines().map(Line::parse).collect();
+
+    if let Some(path) = heatmap {
+        let grid = category_grid(&lines, raster, None);
+        fs::write(path, heatmap::to_pgm(&grid)?)?;
+    }
+
+    if let Some(path) = colour_heatmap {
+        let [orthogonal, diagonal, other] =
+            [Category::Orthogonal, Category::Diagonal, Category::Other]
+                .map(|c| category_grid(&lines, raster, Some(c)));
+        fs::write(path, heatmap::to_ppm(&orthogonal, &diagonal, &other)?)?;
+    }
+
+    if let Some(category) = coverage {
+        print_coverage(&lines, raster, category, k);
+        return Ok(());
+    }
+
+    let (orthogonal_lines, diagonal_lines): (Vec<&Line>, Vec<&Line>) =
+        lines.iter().partition(|l| l.is_orthogonal());
+
+    println!("Task 1: {}", count_overlaps(&orthogonal_lines, raster));
+    println!(
+        "Task 2: {}",
+        count_overlaps(&[orthogonal_lines, diagonal_lines].concat(), raster)
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(line: &str, raster: Raster) -> Vec<(i32, i32)> {
+        Line::parse(line)
+            .points(raster)
+            .map(|p| (p.x, p.y))
+            .collect()
+    }
+
+    #[test]
+    fn test_right_angles_and_diagonals() {
+        for line in ["1,1 -> 1,3", "9,7 -> 7,7", "4,4 -> 4,4"] {
+            assert_eq!(
+                points(line, Raster::Lattice),
+                points(line, Raster::Supercover)
+            );
+        }
+
+        assert_eq!(
+            points("9,7 -> 7,9", Raster::Lattice),
+            vec![(9, 7), (8, 8), (7, 9)]
+        );
+        // Diagonals go through the corners between squares
+        assert_eq!(
+            points("9,7 -> 7,9", Raster::Supercover),
+            vec![(9, 7), (8, 7), (9, 8), (8, 8), (7, 8), (8, 9), (7, 9)]
+        );
+        assert_eq!(points("4,4 -> 4,4", Raster::Lattice), vec![(4, 4)]);
+    }
+
+    #[test]
+    fn test_lattice_points() {
+        // Shallow
+        assert_eq!(
+            points("0,0 -> 6,2", Raster::Lattice),
+            vec![(0, 0), (3, 1), (6, 2)]
+        );
+        // Steep, and going backwards
+        assert_eq!(
+            points("2,9 -> 0,1", Raster::Lattice),
+            vec![(2, 9), (1, 5), (0, 1)]
+        );
+        // No lattice points in between
+        assert_eq!(points("0,0 -> 2,3", Raster::Lattice), vec![(0, 0), (2, 3)]);
+    }
+
+    #[test]
+    fn test_supercover_shallow() {
+        assert_eq!(
+            points("0,0 -> 4,1", Raster::Supercover),
+            vec![(0, 0), (1, 0), (2, 0), (2, 1), (3, 1), (4, 1)]
+        );
+        // Passes through the corner between (1, 0) and (2, 1), so it touches
+        // (2, 0) and (1, 1) as well
+        assert_eq!(
+            points("0,0 -> 3,1", Raster::Supercover),
+            vec![(0, 0), (1, 0), (2, 0), (1, 1), (2, 1), (3, 1)]
+        );
+    }
+
+    #[test]
+    fn test_supercover_steep() {
+        assert_eq!(
+            points("0,0 -> 1,4", Raster::Supercover),
+            vec![(0, 0), (0, 1), (0, 2), (1, 2), (1, 3), (1, 4)]
+        );
+        assert_eq!(
+            points("0,0 -> -1,-4", Raster::Supercover),
+            vec![(0, 0), (0, -1), (0, -2), (-1, -2), (-1, -3), (-1, -4)]
+        );
+    }
+
+    #[test]
+    fn test_supercover_is_connected() {
+        let line = Line::parse("3,-2 -> 17,9");
+        let points: Vec<Vec2> = line.points(Raster::Supercover).collect();
+
+        assert_eq!(points.first(), Some(&line.a));
+        assert_eq!(points.last(), Some(&line.b));
+        assert!(points.windows(2).all(|w| {
+            let step = w[1] - w[0];
+            step.x.abs() <= 1 && step.y.abs() <= 1
+        }));
+    }
+
+    fn example_grid(category: Option<Category>) -> Grid {
+        let lines: Vec<Line> = include_str!("example_input.txt")
+            .lines()
+            .map(Line::parse)
+            .collect();
+
+        category_grid(&lines, Raster::Lattice, category)
     }
 
-    println!("Task 2: {}", grid.output());
+    #[test]
+    fn test_negative_coordinates() {
+        let lines: Vec<Line> = ["-3,-3 -> 3,3", "3,-3 -> -3,3", "-5,0 -> 5,0"]
+            .into_iter()
+            .map(Line::parse)
+            .collect();
+        let grid = category_grid(&lines, Raster::Lattice, None);
+
+        assert_eq!(grid.most_dangerous(), Some((Vec2::new(0, 0), 3)));
+        assert_eq!(
+            count_overlaps(&lines.iter().collect::<Vec<_>>(), Raster::Lattice),
+            1
+        );
+    }
+
+    #[test]
+    fn test_categories() {
+        assert_eq!(Line::parse("0,9 -> 5,9").category(), Category::Orthogonal);
+        assert_eq!(Line::parse("8,0 -> 0,8").category(), Category::Diagonal);
+        assert_eq!(Line::parse("0,0 -> 2,1").category(), Category::Other);
+    }
+
+    #[test]
+    fn test_coverage() {
+        let orthogonal = example_grid(Some(Category::Orthogonal));
+        let all = example_grid(None);
+
+        assert_eq!(orthogonal.output(), 5);
+        assert_eq!(all.output(), 12);
+        assert_eq!(all.at_least(3), 2);
+        assert_eq!(all.at_least(4), 0);
+        assert_eq!(all.at_least(1), all.coverage.len());
+
+        let histogram = all.histogram();
+        assert_eq!(histogram.values().sum::<usize>(), all.coverage.len());
+        assert_eq!(histogram[&2] + histogram[&3], 12);
+    }
+
+    #[test]
+    fn test_most_dangerous() {
+        let (point, count) = example_grid(None).most_dangerous().unwrap();
+
+        // There's also a 3 at 6,4
+        assert_eq!((point.x, point.y, count), (4, 4, 3));
+        assert!(example_grid(Some(Category::Other))
+            .most_dangerous()
+            .is_none());
+    }
 }
@@ -0,0 +1,300 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::{Line, Vec2};
+
+/// Lines that run the same way. Every point is on exactly one line of each
+/// family, which is given by its key `kx * x + ky * y`, and is found along
+/// that line by its position.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+enum Family {
+    Horizontal,
+    Vertical,
+    Diagonal,
+    AntiDiagonal,
+}
+
+impl Family {
+    const ALL: [Family; 4] = [
+        Family::Horizontal,
+        Family::Vertical,
+        Family::Diagonal,
+        Family::AntiDiagonal,
+    ];
+
+    /// The family a line belongs to, if it's a right angle or a diagonal
+    fn of(line: &Line) -> Option<Self> {
+        let dir = line.b - line.a;
+
+        match (dir.x, dir.y) {
+            (_, 0) => Some(Self::Horizontal),
+            (0, _) => Some(Self::Vertical),
+            (x, y) if x == y => Some(Self::Diagonal),
+            (x, y) if x == -y => Some(Self::AntiDiagonal),
+            _ => None,
+        }
+    }
+
+    fn key_coefficients(self) -> (i64, i64) {
+        match self {
+            Self::Horizontal => (0, 1),
+            Self::Vertical => (1, 0),
+            Self::Diagonal => (1, -1),
+            Self::AntiDiagonal => (1, 1),
+        }
+    }
+
+    fn key(self, point: Vec2) -> i64 {
+        let (kx, ky) = self.key_coefficients();
+
+        kx * point.x as i64 + ky * point.y as i64
+    }
+
+    fn position(self, point: Vec2) -> i64 {
+        match self {
+            Self::Vertical => point.y as i64,
+            _ => point.x as i64,
+        }
+    }
+
+    /// The point where two lines from different families cross, if it's a
+    /// lattice point
+    fn crossing(self, key: i64, other: Self, other_key: i64) -> Option<Vec2> {
+        let (a1, b1) = self.key_coefficients();
+        let (a2, b2) = other.key_coefficients();
+        let det = a1 * b2 - a2 * b1;
+
+        let x = key * b2 - other_key * b1;
+        let y = a1 * other_key - a2 * key;
+
+        if x % det != 0 || y % det != 0 {
+            return None;
+        }
+
+        Some(Vec2::new((x / det) as i32, (y / det) as i32))
+    }
+}
+
+/// Inclusive ranges of positions along one line, sorted and not touching
+type Intervals = Vec<(i64, i64)>;
+
+fn contains(intervals: &Intervals, pos: i64) -> bool {
+    let i = intervals.partition_point(|&(_, end)| end < pos);
+
+    i < intervals.len() && intervals[i].0 <= pos
+}
+
+/// Where the segments on one line are covered by at least one, and at least
+/// two segments
+#[derive(Default, Debug)]
+struct Coverage {
+    once: Intervals,
+    twice: Intervals,
+}
+
+impl Coverage {
+    /// Sweeps along the line, keeping track of how many segments we're in
+    fn new(segments: &[(i64, i64)]) -> Self {
+        let mut events: Vec<(i64, i32)> = segments
+            .iter()
+            .flat_map(|&(start, end)| [(start, 1), (end + 1, -1)])
+            .collect();
+        events.sort_unstable();
+
+        let mut coverage = Self::default();
+        let mut depth = 0;
+        let mut once_start = 0;
+        let mut twice_start = 0;
+
+        for (pos, change) in events {
+            let old_depth = depth;
+            depth += change;
+
+            if old_depth < 1 && depth >= 1 {
+                once_start = pos;
+            } else if old_depth >= 1 && depth < 1 {
+                push_interval(&mut coverage.once, once_start, pos - 1);
+            }
+
+            if old_depth < 2 && depth >= 2 {
+                twice_start = pos;
+            } else if old_depth >= 2 && depth < 2 {
+                push_interval(&mut coverage.twice, twice_start, pos - 1);
+            }
+        }
+
+        coverage
+    }
+}
+
+/// Adds an interval to the end of the list, joining it to the last one if
+/// they touch
+fn push_interval(intervals: &mut Intervals, start: i64, end: i64) {
+    match intervals.last_mut() {
+        Some(last) if last.1 + 1 >= start => last.1 = last.1.max(end),
+        _ => intervals.push((start, end)),
+    }
+}
+
+/// Counts the points where at least two lines overlap without going through
+/// every point. Lines are only compared with the lines that run the same way,
+/// apart from where lines of different families cross, so this handles huge
+/// coordinates as long as there aren't too many lines. Returns None if any of
+/// the lines aren't right angles or diagonals.
+pub fn count_overlaps<'a>(lines: impl IntoIterator<Item = &'a Line>) -> Option<usize> {
+    let mut segments: HashMap<Family, BTreeMap<i64, Vec<(i64, i64)>>> = HashMap::new();
+
+    for line in lines {
+        let family = Family::of(line)?;
+        let (a, b) = (family.position(line.a), family.position(line.b));
+
+        segments
+            .entry(family)
+            .or_default()
+            .entry(family.key(line.a))
+            .or_default()
+            .push((a.min(b), a.max(b)));
+    }
+
+    let coverage: HashMap<Family, BTreeMap<i64, Coverage>> = segments
+        .into_iter()
+        .map(|(family, lines)| {
+            let lines = lines
+                .into_iter()
+                .map(|(key, segments)| (key, Coverage::new(&segments)))
+                .collect();
+
+            (family, lines)
+        })
+        .collect();
+
+    let covered = |family: Family, point: Vec2, twice: bool| {
+        coverage
+            .get(&family)
+            .and_then(|lines| lines.get(&family.key(point)))
+            .is_some_and(|c| {
+                let intervals = if twice { &c.twice } else { &c.once };
+                contains(intervals, family.position(point))
+            })
+    };
+
+    // Overlaps between lines in the same family. Lines with different keys
+    // never share a point, so these can just be added up
+    let mut count: i64 = coverage
+        .values()
+        .flat_map(|lines| lines.values())
+        .flat_map(|c| &c.twice)
+        .map(|&(start, end)| end - start + 1)
+        .sum();
+
+    // Points where lines from different families cross
+    let mut crossings = HashSet::new();
+
+    for (i, &family) in Family::ALL.iter().enumerate() {
+        for &other in &Family::ALL[i + 1..] {
+            let (Some(lines), Some(other_lines)) = (coverage.get(&family), coverage.get(&other))
+            else {
+                continue;
+            };
+
+            for &key in lines.keys() {
+                for &other_key in other_lines.keys() {
+                    if let Some(point) = family.crossing(key, other, other_key) {
+                        if covered(family, point, false) && covered(other, point, false) {
+                            crossings.insert(point);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // A crossing might already have been counted as an overlap in one or more
+    // families, so make sure each point is only counted once
+    for point in crossings {
+        let already_counted = Family::ALL
+            .iter()
+            .filter(|&&family| covered(family, point, true))
+            .count() as i64;
+
+        count += 1 - already_counted;
+    }
+
+    Some(count as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Grid, Raster};
+
+    fn parse(input: &str) -> Vec<Line> {
+        input.lines().map(Line::parse).collect()
+    }
+
+    fn count_with_grid(lines: &[Line]) -> usize {
+        let mut grid = Grid::new(Raster::Lattice);
+
+        for line in lines {
+            grid.apply_line(line);
+        }
+
+        grid.output()
+    }
+
+    #[test]
+    fn test_example() {
+        let lines = parse(include_str!("example_input.txt"));
+
+        assert_eq!(
+            count_overlaps(lines.iter().filter(|l| l.is_orthogonal())),
+            Some(5)
+        );
+        assert_eq!(count_overlaps(&lines), Some(12));
+    }
+
+    #[test]
+    fn test_matches_grid() {
+        let (orthogonal, diagonal): (Vec<Line>, Vec<Line>) = parse(include_str!("input.txt"))
+            .into_iter()
+            .partition(|l| l.is_orthogonal());
+
+        assert_eq!(
+            count_overlaps(&orthogonal),
+            Some(count_with_grid(&orthogonal))
+        );
+        assert_eq!(count_overlaps(&diagonal), Some(count_with_grid(&diagonal)));
+        assert_eq!(
+            count_overlaps(orthogonal.iter().chain(&diagonal)),
+            Some(count_with_grid(&[orthogonal, diagonal].concat()))
+        );
+    }
+
+    #[test]
+    fn test_tricky_overlaps() {
+        // Touching ends, a single point, a line inside another, three lines
+        // crossing at one point, and crossings that miss the lattice
+        let lines = parse(
+            "0,0 -> 4,0\n4,0 -> 8,0\n6,0 -> 6,0\n2,0 -> 3,0\n\
+             3,3 -> 3,-3\n0,0 -> 6,6\n0,6 -> 6,0\n0,1 -> 1,0\n0,0 -> 1,1",
+        );
+
+        assert_eq!(count_overlaps(&lines), Some(count_with_grid(&lines)));
+    }
+
+    #[test]
+    fn test_huge_coordinates() {
+        let lines = parse(
+            "0,0 -> 5000000,0\n1000000,0 -> 6000000,0\n\
+             0,-3000000 -> 3000000,0\n2000000,1000000 -> 2000000,-2000000",
+        );
+
+        // The horizontal overlap, which the vertical line and the end of the
+        // diagonal are both on, and the diagonal crossing the vertical line
+        assert_eq!(count_overlaps(&lines), Some(4000001 + 1));
+    }
+
+    #[test]
+    fn test_other_slopes() {
+        assert_eq!(count_overlaps(&parse("0,0 -> 2,1")), None);
+    }
+}
@@ -0,0 +1,190 @@
+use derive_more::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use std::error::Error;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// A 2D integer vector, which can be a point or a direction. Multiplying it by
+/// an integer scales both parts.
+#[derive(
+    Clone, Copy, PartialEq, Eq, Hash, Debug, Default, Add, AddAssign, Sub, SubAssign, Mul, Neg,
+)]
+pub struct Vec2 {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Vec2 {
+    pub const fn new(x: i32, y: i32) -> Self {
+        Self { x, y }
+    }
+
+    pub fn manhattan_distance(self, other: Self) -> u64 {
+        self.x.abs_diff(other.x) as u64 + self.y.abs_diff(other.y) as u64
+    }
+
+    /// The distance if diagonal steps are allowed
+    pub fn chebyshev_distance(self, other: Self) -> u64 {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y)) as u64
+    }
+
+    /// Rotates by 90° anticlockwise, with x to the right and y going up. If y
+    /// goes down, like in the puzzle, this looks clockwise.
+    pub fn rotate_anticlockwise(self) -> Self {
+        Self::new(-self.y, self.x)
+    }
+
+    /// The opposite of `rotate_anticlockwise`
+    pub fn rotate_clockwise(self) -> Self {
+        Self::new(self.y, -self.x)
+    }
+}
+
+impl Display for Vec2 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseVec2Error(String);
+
+impl Display for ParseVec2Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} is not a point like \"x,y\"", self.0)
+    }
+}
+
+impl Error for ParseVec2Error {}
+
+impl FromStr for Vec2 {
+    type Err = ParseVec2Error;
+
+    /// Parses `x,y`, where either can be negative
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let error = || ParseVec2Error(input.to_string());
+        let (x, y) = input.split_once(',').ok_or_else(error)?;
+
+        Ok(Self::new(
+            x.trim().parse().map_err(|_| error())?,
+            y.trim().parse().map_err(|_| error())?,
+        ))
+    }
+}
+
+/// The smallest rectangle containing some points. Both corners are inside it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Bounds {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl Bounds {
+    /// A rectangle with these two opposite corners
+    pub fn new(a: Vec2, b: Vec2) -> Self {
+        Self {
+            min: Vec2::new(a.x.min(b.x), a.y.min(b.y)),
+            max: Vec2::new(a.x.max(b.x), a.y.max(b.y)),
+        }
+    }
+
+    /// The bounds of all of the points, or None if there aren't any
+    pub fn from_points(points: impl IntoIterator<Item = Vec2>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let mut bounds = Self::new(first, first);
+
+        for point in points {
+            bounds.include(point);
+        }
+
+        Some(bounds)
+    }
+
+    /// Grows the bounds to contain the point
+    pub fn include(&mut self, point: Vec2) {
+        self.min = Vec2::new(self.min.x.min(point.x), self.min.y.min(point.y));
+        self.max = Vec2::new(self.max.x.max(point.x), self.max.y.max(point.y));
+    }
+
+    pub fn contains(&self, point: Vec2) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+
+    pub fn width(&self) -> usize {
+        self.min.x.abs_diff(self.max.x) as usize + 1
+    }
+
+    pub fn height(&self) -> usize {
+        self.min.y.abs_diff(self.max.y) as usize + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Vec2::new(3, -4);
+        let b = Vec2::new(-1, 2);
+
+        assert_eq!(a + b, Vec2::new(2, -2));
+        assert_eq!(a - b, Vec2::new(4, -6));
+        assert_eq!(a * 3, Vec2::new(9, -12));
+        assert_eq!(-a, Vec2::new(-3, 4));
+
+        let mut c = a;
+        c += b;
+        c -= b * 2;
+        assert_eq!(c, Vec2::new(4, -6));
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = Vec2::new(3, -4);
+        let b = Vec2::new(-1, 2);
+
+        assert_eq!(a.manhattan_distance(b), 10);
+        assert_eq!(a.chebyshev_distance(b), 6);
+        assert_eq!(a.manhattan_distance(a), 0);
+        assert_eq!(
+            Vec2::new(i32::MIN, i32::MIN).manhattan_distance(Vec2::new(i32::MAX, i32::MAX)),
+            2 * u32::MAX as u64
+        );
+    }
+
+    #[test]
+    fn test_rotation() {
+        let v = Vec2::new(2, 1);
+
+        assert_eq!(v.rotate_anticlockwise(), Vec2::new(-1, 2));
+        assert_eq!(v.rotate_clockwise(), Vec2::new(1, -2));
+        assert_eq!(v.rotate_clockwise().rotate_anticlockwise(), v);
+        assert_eq!(v.rotate_anticlockwise().rotate_anticlockwise(), -v);
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("0,9".parse(), Ok(Vec2::new(0, 9)));
+        assert_eq!("-12, -7".parse(), Ok(Vec2::new(-12, -7)));
+        assert_eq!(
+            "1;2".parse::<Vec2>(),
+            Err(ParseVec2Error("1;2".to_string()))
+        );
+        assert!("1,x".parse::<Vec2>().is_err());
+        assert_eq!(Vec2::new(-3, 4).to_string(), "-3,4");
+    }
+
+    #[test]
+    fn test_bounds() {
+        let bounds =
+            Bounds::from_points([Vec2::new(2, -3), Vec2::new(-1, 5), Vec2::new(0, 0)]).unwrap();
+
+        assert_eq!(bounds, Bounds::new(Vec2::new(2, 5), Vec2::new(-1, -3)));
+        assert_eq!((bounds.width(), bounds.height()), (4, 9));
+        assert!(bounds.contains(Vec2::new(-1, -3)));
+        assert!(!bounds.contains(Vec2::new(3, 0)));
+        assert_eq!(Bounds::from_points([]), None);
+    }
+}
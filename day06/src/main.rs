@@ -1,27 +1,226 @@
-fn main() {
-    let ages = include_str!("input.txt")
-        .split(',')
-        .map(str::parse::<usize>)
-        .map(Result::unwrap);
+use std::env;
+use std::error::Error;
 
-    let mut population: [u64; 9] = [0; 9];
+/// How a lanternfish's life works. Every fish has a timer that counts down
+/// once a day, and when it would go below 0 the fish makes a new fish.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Lifecycle {
+    /// Days between each new fish, so a parent's timer resets to one less
+    spawn_interval: usize,
+    /// Extra days a new fish takes before making its first fish
+    newborn_delay: usize,
+}
+
+impl Lifecycle {
+    const PUZZLE: Self = Self::new(7, 2);
 
-    for age in ages {
-        population[age] += 1;
+    const fn new(spawn_interval: usize, newborn_delay: usize) -> Self {
+        assert!(
+            spawn_interval > 0,
+            "Fish need at least a day between spawns"
+        );
+
+        Self {
+            spawn_interval,
+            newborn_delay,
+        }
     }
 
-    for _ in 0..80 {
-        population.rotate_left(1);
-        // Every time a new lanternfish is created, its parent resets to 6
-        population[6] += population[8];
+    /// Like `new`, but returns an error instead of panicking, for lifecycles
+    /// from the command line
+    fn try_new(spawn_interval: usize, newborn_delay: usize) -> Result<Self, Box<dyn Error>> {
+        if spawn_interval == 0 {
+            return Err("Fish need at least a day between spawns".into());
+        }
+
+        Ok(Self::new(spawn_interval, newborn_delay))
     }
 
-    println!("Task 1: {}", population.iter().sum::<u64>());
+    /// The timer a parent resets to
+    fn reset_timer(&self) -> usize {
+        self.spawn_interval - 1
+    }
 
-    for _ in 0..(256 - 80) {
-        population.rotate_left(1);
-        population[6] += population[8];
+    /// The timer a new fish starts with, which is also the highest timer
+    fn newborn_timer(&self) -> usize {
+        self.reset_timer() + self.newborn_delay
     }
+}
+
+/// How many fish there are with each timer. Since all fish with the same timer
+/// act the same way, we don't need to keep track of each fish. The starting
+/// fish can have longer timers than new ones.
+#[derive(Debug)]
+struct Population {
+    lifecycle: Lifecycle,
+    counts: Vec<u64>,
+    /// Days simulated so far
+    day: usize,
+}
+
+impl Population {
+    fn new(lifecycle: Lifecycle, timers: impl IntoIterator<Item = usize>) -> Self {
+        let mut counts = vec![0; lifecycle.newborn_timer() + 1];
+
+        for timer in timers {
+            if timer >= counts.len() {
+                counts.resize(timer + 1, 0);
+            }
+
+            counts[timer] += 1;
+        }
+
+        Self {
+            lifecycle,
+            counts,
+            day: 0,
+        }
+    }
+
+    /// Moves on by a day, or returns None if a count doesn't fit in a u64
+    fn step(&mut self) -> Option<()> {
+        let parents = self.counts[0];
+        let last = self.counts.len() - 1;
+
+        self.counts.rotate_left(1);
+        self.counts[last] = 0;
+
+        // Every time a new lanternfish is created, its parent resets. With no
+        // newborn delay these are the same timer, which gets both
+        for timer in [self.lifecycle.reset_timer(), self.lifecycle.newborn_timer()] {
+            self.counts[timer] = self.counts[timer].checked_add(parents)?;
+        }
+
+        self.day += 1;
+
+        Some(())
+    }
+
+    fn overflow_error(&self) -> Box<dyn Error> {
+        format!("population overflowed u64 after {} days", self.day).into()
+    }
+
+    fn advance(&mut self, days: usize) -> Result<(), Box<dyn Error>> {
+        for _ in 0..days {
+            self.step().ok_or_else(|| self.overflow_error())?;
+        }
+
+        Ok(())
+    }
+
+    fn size(&self) -> Result<u64, Box<dyn Error>> {
+        self.counts
+            .iter()
+            .try_fold(0u64, |total, &count| total.checked_add(count))
+            .ok_or_else(|| self.overflow_error())
+    }
+}
+
+fn parse_timers(input: &str) -> impl Iterator<Item = usize> + '_ {
+    input.trim().split(',').map(str::parse).map(Result::unwrap)
+}
 
-    println!("Task 2: {}", population.iter().sum::<u64>());
+fn main() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let timers = parse_timers(include_str!("input.txt"));
+
+    // Try a different lifecycle, e.g.
+    // `cargo run -p day06 -- --lifecycle <spawn interval> <newborn delay> <days>`
+    if let [flag, spawn_interval, newborn_delay, days] = args.as_slice() {
+        if flag == "--lifecycle" {
+            let lifecycle = Lifecycle::try_new(spawn_interval.parse()?, newborn_delay.parse()?)?;
+            let mut population = Population::new(lifecycle, timers);
+            population.advance(days.parse()?)?;
+
+            println!("{}", population.size()?);
+
+            return Ok(());
+        }
+    }
+
+    let mut population = Population::new(Lifecycle::PUZZLE, timers);
+
+    population.advance(80)?;
+    println!("Task 1: {}", population.size()?);
+
+    population.advance(256 - 80)?;
+    println!("Task 2: {}", population.size()?);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Simulates every fish on its own
+    fn brute_force(lifecycle: Lifecycle, timers: &[usize], days: usize) -> u64 {
+        let mut fish = timers.to_vec();
+
+        for _ in 0..days {
+            let mut newborns = 0;
+
+            for timer in fish.iter_mut() {
+                if *timer == 0 {
+                    *timer = lifecycle.reset_timer();
+                    newborns += 1;
+                } else {
+                    *timer -= 1;
+                }
+            }
+
+            fish.extend(std::iter::repeat_n(lifecycle.newborn_timer(), newborns));
+        }
+
+        fish.len() as u64
+    }
+
+    #[test]
+    fn test_example() {
+        let timers: Vec<usize> = parse_timers(include_str!("example_input.txt")).collect();
+        let mut population = Population::new(Lifecycle::PUZZLE, timers);
+
+        population.advance(18).unwrap();
+        assert_eq!(population.size().unwrap(), 26);
+        population.advance(80 - 18).unwrap();
+        assert_eq!(population.size().unwrap(), 5934);
+        population.advance(256 - 80).unwrap();
+        assert_eq!(population.size().unwrap(), 26984457539);
+    }
+
+    #[test]
+    fn test_other_lifecycles() {
+        // Some of these are longer than a new fish's timer
+        let timers = [3, 4, 3, 1, 2, 9];
+
+        for (spawn_interval, newborn_delay) in [(7, 2), (3, 4), (5, 0), (1, 1), (1, 0), (10, 6)] {
+            let lifecycle = Lifecycle::new(spawn_interval, newborn_delay);
+            let mut population = Population::new(lifecycle, timers);
+            population.advance(20).unwrap();
+
+            assert_eq!(
+                population.size().unwrap(),
+                brute_force(lifecycle, &timers, 20),
+                "{:?}",
+                lifecycle
+            );
+        }
+    }
+
+    #[test]
+    fn test_overflow() {
+        // Doubles every day
+        let mut population = Population::new(Lifecycle::new(1, 0), [0]);
+
+        assert_eq!(
+            population.advance(100).unwrap_err().to_string(),
+            "population overflowed u64 after 63 days"
+        );
+    }
+
+    #[test]
+    fn test_no_spawn_interval() {
+        assert!(Lifecycle::try_new(0, 2).is_err());
+        assert_eq!(Lifecycle::try_new(7, 2).unwrap(), Lifecycle::PUZZLE);
+    }
 }
@@ -23,7 +23,7 @@ impl Eq for DistEntry {}
 
 impl PartialOrd for DistEntry {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        other.dist.partial_cmp(&self.dist)
+        Some(self.cmp(other))
     }
 }
 
@@ -164,7 +164,7 @@ impl IndexMut<(usize, usize)> for Grid {
 impl Display for Grid {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         for row in self.rows.iter() {
-            for num in row.iter().copied() {
+            for num in row.iter() {
                 write!(f, "{}", num)?;
             }
 
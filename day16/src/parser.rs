@@ -107,6 +107,8 @@ impl Packet {
 }
 
 #[cfg(test)]
+// The bits! macro transmutes its elements to the storage type, which is u8 here
+#[allow(clippy::useless_transmute)]
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
@@ -0,0 +1,77 @@
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+/// An entry in the priority queue. The ordering is reversed so that the
+/// BinaryHeap (which is a max-heap) pops the entry with the smallest distance.
+struct DistEntry<S> {
+    state: S,
+    dist: u32,
+}
+
+impl<S> DistEntry<S> {
+    fn new(state: S, dist: u32) -> Self {
+        Self { state, dist }
+    }
+}
+
+impl<S> PartialEq for DistEntry<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.dist == other.dist
+    }
+}
+
+impl<S> Eq for DistEntry<S> {}
+
+impl<S> PartialOrd for DistEntry<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S> Ord for DistEntry<S> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        other.dist.cmp(&self.dist)
+    }
+}
+
+/// Dijkstra's algorithm over any kind of state. This is a copy of day 15's
+/// `min_risk_path` (days are separate crates, so it can't be shared), changed
+/// to work on states instead of grid positions.
+///
+/// Since we don't know the state space up front, distances are kept in a
+/// HashMap instead of a grid. `neighbors` returns each state reachable from the
+/// given state along with the cost of getting there. Returns `None` if no
+/// target state can be reached.
+pub fn shortest_path<S, I>(
+    start: S,
+    is_target: impl Fn(&S) -> bool,
+    neighbors: impl Fn(&S) -> I,
+) -> Option<u32>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, u32)>,
+{
+    let mut dist: HashMap<S, u32> = HashMap::from([(start.clone(), 0)]);
+    let mut priority_queue: BinaryHeap<DistEntry<S>> = BinaryHeap::from([DistEntry::new(start, 0)]);
+
+    while let Some(min_entry) = priority_queue.pop() {
+        if is_target(&min_entry.state) {
+            return Some(min_entry.dist);
+        }
+
+        if min_entry.dist > dist[&min_entry.state] {
+            continue;
+        }
+
+        for (neighbor, cost) in neighbors(&min_entry.state) {
+            let alt_dist = min_entry.dist.saturating_add(cost);
+
+            if alt_dist < dist.get(&neighbor).copied().unwrap_or(u32::MAX) {
+                dist.insert(neighbor.clone(), alt_dist);
+                priority_queue.push(DistEntry::new(neighbor, alt_dist));
+            }
+        }
+    }
+
+    None
+}
@@ -0,0 +1,280 @@
+use std::fmt::Display;
+
+mod dijkstra;
+
+/// The positions in the hallway directly outside each room. Amphipods can
+/// never stop here.
+const ROOM_ENTRANCES: [usize; 4] = [2, 4, 6, 8];
+const HALLWAY_LENGTH: usize = 11;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Amphipod {
+    Amber,
+    Bronze,
+    Copper,
+    Desert,
+}
+
+impl Amphipod {
+    fn parse(c: char) -> Self {
+        match c {
+            'A' => Self::Amber,
+            'B' => Self::Bronze,
+            'C' => Self::Copper,
+            'D' => Self::Desert,
+            _ => panic!("Unexpected amphipod {}", c),
+        }
+    }
+
+    /// The energy required to move one step
+    fn energy(&self) -> u32 {
+        match self {
+            Self::Amber => 1,
+            Self::Bronze => 10,
+            Self::Copper => 100,
+            Self::Desert => 1000,
+        }
+    }
+
+    /// The index of the room this amphipod wants to end up in
+    fn room(&self) -> usize {
+        match self {
+            Self::Amber => 0,
+            Self::Bronze => 1,
+            Self::Copper => 2,
+            Self::Desert => 3,
+        }
+    }
+}
+
+impl Display for Amphipod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let c = match self {
+            Self::Amber => 'A',
+            Self::Bronze => 'B',
+            Self::Copper => 'C',
+            Self::Desert => 'D',
+        };
+
+        write!(f, "{}", c)
+    }
+}
+
+/// A burrow where each room is DEPTH amphipods deep. Index 0 of a room is the
+/// space closest to the hallway.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Burrow<const DEPTH: usize> {
+    hallway: [Option<Amphipod>; HALLWAY_LENGTH],
+    rooms: [[Option<Amphipod>; DEPTH]; 4],
+}
+
+impl<const DEPTH: usize> Burrow<DEPTH> {
+    fn parse<'a>(lines: impl Iterator<Item = &'a str>) -> Self {
+        let mut rooms = [[None; DEPTH]; 4];
+
+        // Skip the top wall and the hallway
+        for (slot, line) in lines.skip(2).take(DEPTH).enumerate() {
+            let amphipods = line
+                .chars()
+                .filter(char::is_ascii_alphabetic)
+                .map(Amphipod::parse);
+
+            for (room, amphipod) in amphipods.enumerate() {
+                rooms[room][slot] = Some(amphipod);
+            }
+        }
+
+        Self {
+            hallway: [None; HALLWAY_LENGTH],
+            rooms,
+        }
+    }
+
+    /// Is every amphipod in its own room
+    fn is_organised(&self) -> bool {
+        self.rooms.iter().enumerate().all(|(room, spaces)| {
+            spaces
+                .iter()
+                .all(|space| matches!(space, Some(a) if a.room() == room))
+        })
+    }
+
+    /// Does the room only contain amphipods that belong there
+    fn is_settled(&self, room: usize) -> bool {
+        self.rooms[room]
+            .iter()
+            .flatten()
+            .all(|amphipod| amphipod.room() == room)
+    }
+
+    /// The deepest free space in a room, as long as no amphipods that belong
+    /// elsewhere are still in there
+    fn free_space(&self, room: usize) -> Option<usize> {
+        if self.is_settled(room) {
+            self.rooms[room].iter().rposition(Option::is_none)
+        } else {
+            None
+        }
+    }
+
+    /// Can an amphipod move along the hallway from `from` to `to`. The space
+    /// at `from` isn't checked, since that's where the amphipod is.
+    fn hallway_clear(&self, from: usize, to: usize) -> bool {
+        let spaces = if from < to {
+            &self.hallway[(from + 1)..=to]
+        } else {
+            &self.hallway[to..from]
+        };
+
+        spaces.iter().all(Option::is_none)
+    }
+
+    /// Returns every burrow that can be reached with a single move, along with
+    /// the energy that move took
+    fn moves(&self) -> Vec<(Self, u32)> {
+        let mut moves = Vec::new();
+
+        // Amphipods in the hallway can only move into their own room
+        for (position, amphipod) in self
+            .hallway
+            .iter()
+            .enumerate()
+            .filter_map(|(i, space)| space.map(|a| (i, a)))
+        {
+            let room = amphipod.room();
+            let entrance = ROOM_ENTRANCES[room];
+
+            if let Some(slot) = self.free_space(room) {
+                if self.hallway_clear(position, entrance) {
+                    let mut next = *self;
+                    next.hallway[position] = None;
+                    next.rooms[room][slot] = Some(amphipod);
+
+                    let steps = position.abs_diff(entrance) + slot + 1;
+                    moves.push((next, steps as u32 * amphipod.energy()));
+                }
+            }
+        }
+
+        // The amphipod closest to the hallway in each room can move out into
+        // the hallway, unless the room is already sorted
+        for room in (0..4).filter(|&r| !self.is_settled(r)) {
+            let entrance = ROOM_ENTRANCES[room];
+            let (slot, amphipod) = self.rooms[room]
+                .iter()
+                .enumerate()
+                .find_map(|(i, space)| space.map(|a| (i, a)))
+                .unwrap();
+
+            for position in (0..HALLWAY_LENGTH).filter(|p| !ROOM_ENTRANCES.contains(p)) {
+                if self.hallway_clear(entrance, position) {
+                    let mut next = *self;
+                    next.rooms[room][slot] = None;
+                    next.hallway[position] = Some(amphipod);
+
+                    let steps = slot + 1 + entrance.abs_diff(position);
+                    moves.push((next, steps as u32 * amphipod.energy()));
+                }
+            }
+        }
+
+        moves
+    }
+
+    /// The least energy required to organise the amphipods
+    fn min_energy(&self) -> u32 {
+        dijkstra::shortest_path(*self, Self::is_organised, Self::moves).unwrap()
+    }
+}
+
+impl Burrow<2> {
+    /// Inserts the two extra rows from the folded part of the diagram
+    fn unfold(&self) -> Burrow<4> {
+        let extra_rows = Burrow::<2>::parse(["", "", "  #D#C#B#A#", "  #D#B#A#C#"].into_iter());
+
+        let mut rooms = [[None; 4]; 4];
+
+        for (room, spaces) in rooms.iter_mut().enumerate() {
+            *spaces = [
+                self.rooms[room][0],
+                extra_rows.rooms[room][0],
+                extra_rows.rooms[room][1],
+                self.rooms[room][1],
+            ];
+        }
+
+        Burrow {
+            hallway: self.hallway,
+            rooms,
+        }
+    }
+}
+
+impl<const DEPTH: usize> Display for Burrow<DEPTH> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let space = |s: Option<Amphipod>| s.map_or(".".to_string(), |a| a.to_string());
+
+        writeln!(f, "#############")?;
+        write!(f, "#")?;
+        for s in self.hallway {
+            write!(f, "{}", space(s))?;
+        }
+        writeln!(f, "#")?;
+
+        for slot in 0..DEPTH {
+            write!(f, "{}", if slot == 0 { "##" } else { "  " })?;
+            for room in self.rooms {
+                write!(f, "#{}", space(room[slot]))?;
+            }
+            writeln!(f, "{}", if slot == 0 { "###" } else { "#" })?;
+        }
+
+        writeln!(f, "  #########")
+    }
+}
+
+fn main() {
+    let input = include_str!("input.txt");
+
+    if input.trim().is_empty() {
+        println!("Put your puzzle input in day23/src/input.txt");
+        return;
+    }
+
+    let burrow: Burrow<2> = Burrow::parse(input.lines());
+
+    println!("Task 1: {}", burrow.min_energy());
+    println!("Task 2: {}", burrow.unfold().min_energy());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_task1_example() {
+        let burrow: Burrow<2> = Burrow::parse(include_str!("example_input.txt").lines());
+        assert_eq!(burrow.min_energy(), 12521);
+    }
+
+    #[test]
+    fn test_task2_example() {
+        let burrow: Burrow<2> = Burrow::parse(include_str!("example_input.txt").lines());
+        assert_eq!(burrow.unfold().min_energy(), 44169);
+    }
+
+    #[test]
+    fn test_unfold() {
+        let burrow: Burrow<2> = Burrow::parse(include_str!("example_input.txt").lines());
+        let expected = "\
+#############
+#...........#
+###B#C#B#D###
+  #D#C#B#A#
+  #D#B#A#C#
+  #A#D#C#A#
+  #########
+";
+        assert_eq!(burrow.unfold().to_string(), expected);
+    }
+}
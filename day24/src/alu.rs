@@ -0,0 +1,223 @@
+use std::ops::{Index, IndexMut};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Register {
+    W,
+    X,
+    Y,
+    Z,
+}
+
+impl Register {
+    fn parse(input: &str) -> Self {
+        match input {
+            "w" => Self::W,
+            "x" => Self::X,
+            "y" => Self::Y,
+            "z" => Self::Z,
+            _ => panic!("Unexpected register {}", input),
+        }
+    }
+}
+
+/// The second argument of an instruction, which can be a register or a number
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
+    Register(Register),
+    Number(i64),
+}
+
+impl Operand {
+    fn parse(input: &str) -> Self {
+        match input.parse() {
+            Ok(num) => Self::Number(num),
+            Err(_) => Self::Register(Register::parse(input)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Inp(Register),
+    Add(Register, Operand),
+    Mul(Register, Operand),
+    Div(Register, Operand),
+    Mod(Register, Operand),
+    Eql(Register, Operand),
+}
+
+impl Instruction {
+    pub fn parse(input: &str) -> Self {
+        let mut tokens = input.split_whitespace();
+
+        let op = tokens.next().expect("No operation found");
+        let a = Register::parse(tokens.next().expect("No register found"));
+
+        if op == "inp" {
+            return Self::Inp(a);
+        }
+
+        let b = Operand::parse(tokens.next().expect("No second argument found"));
+
+        match op {
+            "add" => Self::Add(a, b),
+            "mul" => Self::Mul(a, b),
+            "div" => Self::Div(a, b),
+            "mod" => Self::Mod(a, b),
+            "eql" => Self::Eql(a, b),
+            _ => panic!("Unexpected instruction {}", input),
+        }
+    }
+}
+
+/// Parses a whole program, one instruction per line
+pub fn parse_program<'a>(lines: impl Iterator<Item = &'a str>) -> Vec<Instruction> {
+    lines
+        .filter(|l| !l.is_empty())
+        .map(Instruction::parse)
+        .collect()
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Alu {
+    registers: [i64; 4],
+}
+
+impl Alu {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn value(&self, operand: Operand) -> i64 {
+        match operand {
+            Operand::Register(reg) => self[reg],
+            Operand::Number(num) => num,
+        }
+    }
+
+    /// Runs the program, reading from `input` each time there's an `inp`
+    /// instruction. Like the real ALU, this will crash on invalid division or
+    /// modulo operations, or if it runs out of input.
+    pub fn run(&mut self, program: &[Instruction], mut input: impl Iterator<Item = i64>) {
+        for &instruction in program {
+            match instruction {
+                Instruction::Inp(a) => self[a] = input.next().expect("Ran out of input"),
+                Instruction::Add(a, b) => self[a] += self.value(b),
+                Instruction::Mul(a, b) => self[a] *= self.value(b),
+                Instruction::Div(a, b) => {
+                    let b = self.value(b);
+                    assert!(b != 0, "Attempted to divide by zero");
+
+                    // Rust's integer division already truncates towards zero
+                    self[a] /= b;
+                }
+                Instruction::Mod(a, b) => {
+                    let b = self.value(b);
+                    assert!(self[a] >= 0 && b > 0, "Invalid modulo operation");
+
+                    self[a] %= b;
+                }
+                Instruction::Eql(a, b) => self[a] = (self[a] == self.value(b)) as i64,
+            }
+        }
+    }
+}
+
+impl Index<Register> for Alu {
+    type Output = i64;
+
+    fn index(&self, index: Register) -> &Self::Output {
+        &self.registers[index as usize]
+    }
+}
+
+impl IndexMut<Register> for Alu {
+    fn index_mut(&mut self, index: Register) -> &mut Self::Output {
+        &mut self.registers[index as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(program: &str, input: &[i64]) -> Alu {
+        let program = parse_program(program.lines());
+        let mut alu = Alu::new();
+        alu.run(&program, input.iter().copied());
+        alu
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Instruction::parse("inp w"), Instruction::Inp(Register::W));
+        assert_eq!(
+            Instruction::parse("add z y"),
+            Instruction::Add(Register::Z, Operand::Register(Register::Y))
+        );
+        assert_eq!(
+            Instruction::parse("div z 26"),
+            Instruction::Div(Register::Z, Operand::Number(26))
+        );
+        assert_eq!(
+            Instruction::parse("add x -11"),
+            Instruction::Add(Register::X, Operand::Number(-11))
+        );
+    }
+
+    #[test]
+    fn test_negate() {
+        let alu = run("inp x\nmul x -1", &[7]);
+        assert_eq!(alu[Register::X], -7);
+    }
+
+    #[test]
+    fn test_three_times_larger() {
+        let program = "inp z\ninp x\nmul z 3\neql z x";
+        assert_eq!(run(program, &[2, 6])[Register::Z], 1);
+        assert_eq!(run(program, &[2, 5])[Register::Z], 0);
+    }
+
+    #[test]
+    fn test_binary() {
+        let program = "\
+inp w
+add z w
+mod z 2
+div w 2
+add y w
+mod y 2
+div w 2
+add x w
+mod x 2
+div w 2
+mod w 2";
+        let alu = run(program, &[0b1011]);
+        assert_eq!(alu.registers, [1, 0, 1, 1]);
+    }
+
+    #[test]
+    fn test_div_truncates_towards_zero() {
+        let alu = run("inp x\ndiv x 2\ninp y\ndiv y 2", &[-7, 7]);
+        assert_eq!(alu[Register::X], -3);
+        assert_eq!(alu[Register::Y], 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "divide by zero")]
+    fn test_div_by_zero() {
+        run("inp x\ndiv x 0", &[1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid modulo")]
+    fn test_mod_negative() {
+        run("inp x\nmod x 5", &[-1]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Ran out of input")]
+    fn test_out_of_input() {
+        run("inp x\ninp y", &[1]);
+    }
+}
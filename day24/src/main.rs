@@ -0,0 +1,146 @@
+use alu::{Alu, Instruction, Operand, Register};
+
+mod alu;
+
+const BLOCK_LENGTH: usize = 18;
+
+/// MONAD is made up of 14 blocks which each read one digit of the model number.
+/// The blocks are identical except for three numbers, which are stored here.
+///
+/// Each block treats z as a stack of base 26 digits. A block either just pushes
+/// `digit + offset` onto the stack, or it pops the top value and, unless that
+/// value plus `check` equals the digit, pushes `digit + offset` back on. The
+/// model number is valid if the stack is empty at the end.
+#[derive(Debug)]
+struct Block {
+    pops: bool,
+    check: i64,
+    offset: i64,
+}
+
+impl Block {
+    fn parse(instructions: &[Instruction]) -> Self {
+        assert_eq!(instructions.len(), BLOCK_LENGTH, "Unexpected block length");
+
+        match (instructions[4], instructions[5], instructions[15]) {
+            (
+                Instruction::Div(Register::Z, Operand::Number(div)),
+                Instruction::Add(Register::X, Operand::Number(check)),
+                Instruction::Add(Register::Y, Operand::Number(offset)),
+            ) => Self {
+                pops: div == 26,
+                check,
+                offset,
+            },
+            _ => panic!("Unexpected block structure"),
+        }
+    }
+}
+
+/// Pairs up the pushing and popping blocks. For each pair `(i, j, diff)`, the
+/// model number is only valid if `digits[j] == digits[i] + diff`.
+fn constraints(blocks: &[Block]) -> Vec<(usize, usize, i64)> {
+    let mut stack: Vec<usize> = Vec::new();
+    let mut constraints = Vec::new();
+
+    for (j, block) in blocks.iter().enumerate() {
+        if block.pops {
+            let i = stack.pop().expect("More pops than pushes");
+            let diff = blocks[i].offset + block.check;
+
+            // Each digit has to be between 1 and 9
+            assert!(diff.abs() < 9, "Blocks {} and {} can't be satisfied", i, j);
+
+            constraints.push((i, j, diff));
+        } else {
+            stack.push(j);
+        }
+    }
+
+    assert!(stack.is_empty(), "More pushes than pops");
+
+    constraints
+}
+
+/// Finds the largest or smallest model number that satisfies the constraints
+fn model_number(constraints: &[(usize, usize, i64)], largest: bool) -> Vec<i64> {
+    let mut digits = vec![0; constraints.len() * 2];
+
+    for &(i, j, diff) in constraints {
+        digits[i] = if largest {
+            (9 - diff).min(9)
+        } else {
+            (1 - diff).max(1)
+        };
+        digits[j] = digits[i] + diff;
+    }
+
+    digits
+}
+
+fn digits_to_num(digits: &[i64]) -> i64 {
+    digits.iter().fold(0, |acc, digit| acc * 10 + digit)
+}
+
+/// Runs MONAD on the model number to double check it's valid
+fn is_valid(program: &[Instruction], digits: &[i64]) -> bool {
+    let mut alu = Alu::new();
+    alu.run(program, digits.iter().copied());
+
+    alu[Register::Z] == 0
+}
+
+fn main() {
+    let input = include_str!("input.txt");
+
+    if input.trim().is_empty() {
+        println!("Put your puzzle input in day24/src/input.txt");
+        return;
+    }
+
+    let program = alu::parse_program(input.lines());
+    let blocks: Vec<Block> = program.chunks(BLOCK_LENGTH).map(Block::parse).collect();
+    let constraints = constraints(&blocks);
+
+    let largest = model_number(&constraints, true);
+    assert!(is_valid(&program, &largest));
+    println!("Task 1: {}", digits_to_num(&largest));
+
+    let smallest = model_number(&constraints, false);
+    assert!(is_valid(&program, &smallest));
+    println!("Task 2: {}", digits_to_num(&smallest));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(pops: bool, check: i64, offset: i64) -> Block {
+        Block {
+            pops,
+            check,
+            offset,
+        }
+    }
+
+    #[test]
+    fn test_solver() {
+        let blocks = [
+            block(false, 12, 3),
+            block(false, 11, 7),
+            block(true, -10, 5),
+            block(true, -1, 8),
+        ];
+        let constraints = constraints(&blocks);
+
+        assert_eq!(constraints, vec![(1, 2, -3), (0, 3, 2)]);
+        assert_eq!(digits_to_num(&model_number(&constraints, true)), 7969);
+        assert_eq!(digits_to_num(&model_number(&constraints, false)), 1413);
+    }
+
+    #[test]
+    #[should_panic(expected = "can't be satisfied")]
+    fn test_unsatisfiable() {
+        constraints(&[block(false, 12, 9), block(true, 0, 0)]);
+    }
+}
@@ -0,0 +1,109 @@
+use std::fmt::Display;
+
+use torus::TorusGrid;
+
+mod torus;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cell {
+    Empty,
+    East,
+    South,
+}
+
+impl Cell {
+    fn parse(c: char) -> Self {
+        match c {
+            '.' => Self::Empty,
+            '>' => Self::East,
+            'v' => Self::South,
+            _ => panic!("Unexpected cell {}", c),
+        }
+    }
+}
+
+impl Display for Cell {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let c = match self {
+            Self::Empty => '.',
+            Self::East => '>',
+            Self::South => 'v',
+        };
+
+        write!(f, "{}", c)
+    }
+}
+
+/// Moves every sea cucumber in the herd one space in `direction` if there's
+/// room. All of them look before any of them move. Returns whether any of them
+/// moved.
+fn move_herd(grid: &mut TorusGrid<Cell>, herd: Cell, direction: (isize, isize)) -> bool {
+    let moving: Vec<((usize, usize), (usize, usize))> = grid
+        .points()
+        .filter(|&p| grid[p] == herd)
+        .map(|p| (p, grid.offset(p, direction)))
+        .filter(|&(_, next)| grid[next] == Cell::Empty)
+        .collect();
+
+    for &(point, next) in moving.iter() {
+        grid[point] = Cell::Empty;
+        grid[next] = herd;
+    }
+
+    !moving.is_empty()
+}
+
+/// Returns the first step on which no sea cucumbers move
+fn steps_until_stopped(mut grid: TorusGrid<Cell>) -> usize {
+    let mut step = 1;
+
+    loop {
+        // The east-facing herd always moves first. Both herds have to be
+        // moved, so don't short circuit here
+        let east_moved = move_herd(&mut grid, Cell::East, (0, 1));
+        let south_moved = move_herd(&mut grid, Cell::South, (1, 0));
+
+        if !east_moved && !south_moved {
+            break step;
+        }
+
+        step += 1;
+    }
+}
+
+fn main() {
+    let input = include_str!("input.txt");
+
+    if input.trim().is_empty() {
+        println!("Put your puzzle input in day25/src/input.txt");
+        return;
+    }
+
+    let grid = TorusGrid::parse(input.lines(), Cell::parse);
+
+    println!("Task 1: {}", steps_until_stopped(grid));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_task1_example() {
+        let grid = TorusGrid::parse(include_str!("example_input.txt").lines(), Cell::parse);
+        assert_eq!(steps_until_stopped(grid), 58);
+    }
+
+    #[test]
+    fn test_wrapping() {
+        let mut grid = TorusGrid::parse("...>>>>>...".lines(), Cell::parse);
+
+        move_herd(&mut grid, Cell::East, (0, 1));
+        assert_eq!(grid.to_string(), "...>>>>.>..\n");
+
+        move_herd(&mut grid, Cell::East, (0, 1));
+        move_herd(&mut grid, Cell::East, (0, 1));
+        move_herd(&mut grid, Cell::East, (0, 1));
+        assert_eq!(grid.to_string(), ">..>.>.>.>.\n");
+    }
+}
@@ -0,0 +1,89 @@
+use std::fmt::Display;
+use std::ops::{Index, IndexMut};
+
+/// A grid where moving off one edge brings you back on the opposite edge.
+/// Points are (row, column), like the grids from the other days.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TorusGrid<T> {
+    cells: Vec<T>,
+    columns: usize,
+}
+
+impl<T> TorusGrid<T> {
+    /// Builds a grid by parsing each character with `parse_cell`
+    pub fn parse<'a>(lines: impl Iterator<Item = &'a str>, parse_cell: impl Fn(char) -> T) -> Self {
+        let mut cells = Vec::new();
+        let mut columns = None;
+
+        for line in lines.filter(|l| !l.is_empty()) {
+            let len_before = cells.len();
+            cells.extend(line.chars().map(&parse_cell));
+
+            let row_len = cells.len() - len_before;
+            assert_eq!(
+                *columns.get_or_insert(row_len),
+                row_len,
+                "Rows have different lengths"
+            );
+        }
+
+        Self {
+            cells,
+            columns: columns.expect("Grid is empty"),
+        }
+    }
+
+    /// Returns the height and width, in that order
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.cells.len() / self.columns, self.columns)
+    }
+
+    /// Moves a point by the given offset, wrapping around the edges
+    pub fn offset(&self, point: (usize, usize), offset: (isize, isize)) -> (usize, usize) {
+        let (rows, columns) = self.dimensions();
+
+        (
+            (point.0 as isize + offset.0).rem_euclid(rows as isize) as usize,
+            (point.1 as isize + offset.1).rem_euclid(columns as isize) as usize,
+        )
+    }
+
+    /// An iterator of every point in the grid, row by row
+    pub fn points(&self) -> impl Iterator<Item = (usize, usize)> {
+        let (rows, columns) = self.dimensions();
+
+        (0..rows).flat_map(move |i| (0..columns).map(move |j| (i, j)))
+    }
+}
+
+impl<T> Index<(usize, usize)> for TorusGrid<T> {
+    type Output = T;
+
+    fn index(&self, index: (usize, usize)) -> &Self::Output {
+        let (row, column) = index;
+
+        &self.cells[row * self.columns + column]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for TorusGrid<T> {
+    fn index_mut(&mut self, index: (usize, usize)) -> &mut Self::Output {
+        let (row, column) = index;
+
+        &mut self.cells[row * self.columns + column]
+    }
+}
+
+impl<T: Display> Display for TorusGrid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.cells.chunks(self.columns) {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}